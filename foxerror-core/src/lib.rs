@@ -0,0 +1,4748 @@
+//! parsing and code generation behind foxerror's macros
+//!
+//! everything here works on [`proc_macro2::TokenStream`]s, so unlike the
+//! macros themselves it can be called from tests and other ordinary code.
+//! this crate is an implementation detail of foxerror and has no stability
+//! guarantees of its own.
+//!
+//! ```rust
+//! let output = foxerror_core::derive(quote::quote! {
+//!     enum Error {
+//!         /// not found
+//!         NotFound,
+//!     }
+//! });
+//! let output = output.to_string();
+//! assert!(output.contains("impl :: core :: fmt :: Display for Error"));
+//! assert!(output.contains("\"not found\""));
+//! ```
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    DeriveInput, Token,
+};
+
+struct ParsedErrors {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    variants: Vec<Variant>,
+    asserts: Vec<TokenStream>,
+    impl_exit_code: bool,
+    /// the name of the error FromStr gives
+    impl_from_display: Option<syn::Ident>,
+    module: Option<(syn::Visibility, syn::Ident)>,
+    context_chain: bool,
+    with_depth: bool,
+    display_fields: bool,
+    variant_index: bool,
+    variants_count: bool,
+    deny_duplicate_messages: bool,
+    hash_by_code: bool,
+    variant_name: VariantName,
+    std_error_v2: bool,
+    vis: syn::Visibility,
+    result_alias: Option<syn::Ident>,
+    machine_readable: bool,
+    serde: bool,
+    write_to: bool,
+    message_consts: bool,
+    subsets: Vec<Subset>,
+    subset_args: Vec<AttrArg>,
+    krate: TokenStream,
+    /// where ErrorMeta and MultiError come from
+    traits_crate: TokenStream,
+    opaque: Option<(syn::Visibility, syn::Ident)>,
+    into: Option<(syn::Expr, Option<syn::Expr>)>,
+    infallible_from: bool,
+    track_caller: bool,
+    on_display: Option<syn::Path>,
+    strict: bool,
+    boxed: Option<syn::Ident>,
+    /// implemented instead of `core::error::Error`
+    error_trait: Option<syn::Path>,
+    error_kind: bool,
+    error_meta: bool,
+    additive: bool,
+    static_table: bool,
+    color: bool,
+    pretty: bool,
+    method_vis: MethodVis,
+    tracing_event: Option<syn::Ident>,
+    /// a cfg predicate under which Display leaves out the messages
+    strip_messages: Option<TokenStream>,
+}
+
+struct Variant {
+    ident: syn::Ident,
+    style: Style,
+    fields: Vec<Field>,
+    msg: Option<Msg>,
+    /// a field shown in front of the message instead of with the others
+    prefix_field: Option<usize>,
+    from: bool,
+    transparent: bool,
+    other: bool,
+    exit_code: Option<syn::Expr>,
+    code: Option<syn::Expr>,
+    template: Option<Vec<TemplatePart>>,
+    terminator: Option<String>,
+    into: Option<syn::Expr>,
+    severity: Option<syn::Ident>,
+    fmt_if: Option<syn::Expr>,
+    tracing_level: Option<syn::Ident>,
+    as_static: bool,
+    key: Option<String>,
+    discriminant: Option<syn::Expr>,
+    /// the variant as written, only kept around for subsets to copy
+    def: Option<syn::Variant>,
+}
+
+enum Msg {
+    Str(String),
+    Macro(syn::ExprMacro),
+    /// a `&str` constant, or a block working one out at compile time
+    Const(syn::Expr),
+}
+
+impl Default for Msg {
+    fn default() -> Self {
+        Self::Str(String::new())
+    }
+}
+
+impl Msg {
+    fn prefix(self, prefix: &str) -> Self {
+        match self {
+            Self::Str(msg) => Self::Str(format!("{prefix}{msg}")),
+            Self::Macro(msg) => Self::Macro(syn::parse_quote!(::core::concat!(#prefix, #msg))),
+            Self::Const(msg) => Self::Const(const_concat(quote!(#prefix), quote!(#msg))),
+        }
+    }
+
+    /// turn doubled braces into single ones, for messages written out as-is
+    /// rather than split up with parse_msg
+    fn unescape(self) -> Self {
+        match self {
+            Self::Str(msg) => Self::Str(msg.replace("{{", "{").replace("}}", "}")),
+            msg => msg,
+        }
+    }
+
+    fn suffix(self, suffix: &str) -> Self {
+        match self {
+            Self::Str(msg) => Self::Str(msg + suffix),
+            Self::Macro(msg) => Self::Macro(syn::parse_quote!(::core::concat!(#msg, #suffix))),
+            Self::Const(msg) if suffix.is_empty() => Self::Const(msg),
+            Self::Const(msg) => Self::Const(const_concat(quote!(#msg), quote!(#suffix))),
+        }
+    }
+}
+
+/// join two `&str` constants at compile time, since `concat!` only takes
+/// literals
+fn const_concat(a: TokenStream, b: TokenStream) -> syn::Expr {
+    syn::Expr::Verbatim(quote!({
+        const PARTS: [&str; 2] = [#a, #b];
+        const LEN: usize = PARTS[0].len() + PARTS[1].len();
+        const BYTES: [u8; LEN] = {
+            let mut bytes = [0; LEN];
+            let mut at = 0;
+            let mut part = 0;
+            while part < PARTS.len() {
+                let mut i = 0;
+                while i < PARTS[part].len() {
+                    bytes[at] = PARTS[part].as_bytes()[i];
+                    at += 1;
+                    i += 1;
+                }
+                part += 1;
+            }
+            bytes
+        };
+        const MSG: &str = match ::core::str::from_utf8(&BYTES) {
+            ::core::result::Result::Ok(msg) => msg,
+            ::core::result::Result::Err(_) => ::core::panic!("joined strings are always utf-8"),
+        };
+        MSG
+    }))
+}
+
+impl quote::ToTokens for Msg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Str(msg) => msg.to_tokens(tokens),
+            Self::Macro(msg) => msg.to_tokens(tokens),
+            Self::Const(msg) => msg.to_tokens(tokens),
+        }
+    }
+}
+
+/// visibility of generated inherent methods
+struct MethodVis {
+    default: syn::Visibility,
+    overrides: Vec<(String, syn::Visibility)>,
+}
+
+impl MethodVis {
+    const METHODS: &[&str] = &[
+        "other",
+        "exit_code",
+        "error_code",
+        "display_fields",
+        "sources",
+        "to_info",
+        "kind",
+        "into_inner",
+        "variant_index",
+        "leak",
+        "message_key",
+        "write_to",
+        "variants_count",
+    ];
+
+    fn parse(args: &[AttrArg]) -> syn::Result<Self> {
+        let default = match args.iter().rfind(|a| a.ident == "vis") {
+            Some(a) => a.parse_str("a visibility")?,
+            None => syn::parse_quote!(pub),
+        };
+        let mut overrides = vec![];
+        for a in args {
+            let name = a.ident.to_string();
+            let Some(method) = name.strip_suffix("_vis") else {
+                continue;
+            };
+            if Self::METHODS.contains(&method) {
+                overrides.push((method.to_string(), a.parse_str("a visibility")?));
+            }
+        }
+        Ok(Self { default, overrides })
+    }
+
+    fn get(&self, method: &str) -> &syn::Visibility {
+        self.overrides
+            .iter()
+            .rfind(|(m, _)| m == method)
+            .map_or(&self.default, |(_, vis)| vis)
+    }
+}
+
+enum VariantName {
+    Auto,
+    Always,
+    Never,
+}
+
+/// how much of a doc comment becomes the message
+#[derive(Clone, Copy)]
+enum MsgDoc {
+    First,
+    Paragraph,
+    All,
+}
+
+enum Style {
+    Named,
+    Unnamed,
+    Unit,
+}
+
+struct Field {
+    ident: Option<syn::Ident>,
+    ty: syn::Type,
+    /// where the type is, worked out once since it means walking its tokens
+    span: Span,
+    quote: bool,
+    source: bool,
+    skip: bool,
+    /// shown in front of the message instead of with the other fields
+    prefix: bool,
+    implicit: Option<TokenStream>,
+    render: Render,
+}
+
+impl Field {
+    /// whether the field is part of the displayed message
+    fn shown(&self) -> bool {
+        (!self.source || matches!(self.render, Render::Debug))
+            && self.implicit.is_none()
+            && !self.skip
+    }
+
+    /// whether the field is shown in the list after the message
+    fn listed(&self) -> bool {
+        self.shown() && !self.prefix
+    }
+}
+
+enum Render {
+    Display,
+    Debug,
+    Duration,
+    Path,
+    List(Option<String>),
+    Bool(String, String),
+    /// a type implementing `From<&T>` and `Display` to show the field with
+    Via(syn::Expr),
+}
+
+#[derive(Clone, Default)]
+struct ListStyle {
+    bullets: Option<bool>,
+    bullet: Option<String>,
+}
+
+impl ListStyle {
+    fn parse(args: &[AttrArg]) -> syn::Result<Self> {
+        let bullets = args
+            .iter()
+            .rfind(|a| a.ident == "field_list_style")
+            .map(|a| match a.str()?.as_str() {
+                "bullets" => Ok(true),
+                "inline" => Ok(false),
+                _ => Err(a.error("field_list_style must be one of bullets or inline")),
+            })
+            .transpose()?;
+        let bullet = args
+            .iter()
+            .rfind(|a| a.ident == "bullet")
+            .map(AttrArg::str_raw)
+            .transpose()?;
+        Ok(Self { bullets, bullet })
+    }
+
+    fn or(self, other: &Self) -> Self {
+        Self {
+            bullets: self.bullets.or(other.bullets),
+            bullet: self.bullet.or_else(|| other.bullet.clone()),
+        }
+    }
+}
+
+struct AttrArg {
+    ident: syn::Ident,
+    value: Option<syn::Expr>,
+    list: Option<TokenStream>,
+}
+
+impl Parse for AttrArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.call(syn::Ident::parse_any)?;
+        let list = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            Some(content.parse()?)
+        } else {
+            None
+        };
+        let value = if input.parse::<Token![=]>().is_ok() {
+            Some(parse_expr(input)?)
+        } else {
+            None
+        };
+        Ok(Self { ident, value, list })
+    }
+}
+
+/// parse an expression up to the next comma, keeping anything syn can't
+/// make sense of without its full grammar, like closures, as it is
+fn parse_expr(input: ParseStream) -> syn::Result<syn::Expr> {
+    // most values are plain literals, which don't need the whole expression
+    // parser
+    let alone = |rest: syn::buffer::Cursor| {
+        rest.eof() || rest.punct().is_some_and(|(p, _)| p.as_char() == ',')
+    };
+    if input.peek(syn::Lit)
+        && input
+            .cursor()
+            .token_tree()
+            .is_some_and(|(_, rest)| alone(rest))
+    {
+        return Ok(syn::Expr::Lit(syn::ExprLit {
+            attrs: vec![],
+            lit: input.parse()?,
+        }));
+    }
+    let fork = input.fork();
+    if let Ok(expr) = fork.parse::<syn::Expr>() {
+        if fork.is_empty() || fork.peek(Token![,]) {
+            syn::parse::discouraged::Speculative::advance_to(input, &fork);
+            return Ok(expr);
+        }
+    }
+    // commas between angle brackets, like in wrap::<u8, _>, don't end the
+    // value, while the > of an arrow doesn't close anything
+    let mut tokens = TokenStream::new();
+    let mut depth = 0usize;
+    let mut joined = None;
+    while !input.is_empty() && (depth > 0 || !input.peek(Token![,])) {
+        let token = input.parse::<proc_macro2::TokenTree>()?;
+        joined = match token {
+            proc_macro2::TokenTree::Punct(ref p) => {
+                match p.as_char() {
+                    '<' if !input.peek(Token![=]) => depth += 1,
+                    '>' if !matches!(joined, Some('-' | '=')) => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                (p.spacing() == proc_macro2::Spacing::Joint).then(|| p.as_char())
+            }
+            _ => None,
+        };
+        tokens.extend([token]);
+    }
+    if tokens.is_empty() {
+        return Err(input.error("expected an expression"));
+    }
+    Ok(syn::Expr::Verbatim(tokens))
+}
+
+impl AttrArg {
+    fn error(&self, msg: impl std::fmt::Display) -> syn::Error {
+        syn::Error::new_spanned(self, msg)
+    }
+
+    fn value(&self) -> syn::Result<&syn::Expr> {
+        self.value
+            .as_ref()
+            .ok_or_else(|| self.error(format_args!("{} requires a value", self.ident)))
+    }
+
+    fn str(&self) -> syn::Result<String> {
+        let s = self.str_raw()?;
+        Ok(s.strip_prefix(' ').unwrap_or(&s).to_string())
+    }
+
+    fn str_raw(&self) -> syn::Result<String> {
+        let value = self.value()?;
+        expr_str_raw(value).ok_or_else(|| {
+            let msg = format!("expected a string literal here for {}", self.ident);
+            syn::Error::new_spanned(value, msg)
+        })
+    }
+
+    /// a flag, given either on its own or as `= true` or `= false`
+    fn flag(&self) -> syn::Result<bool> {
+        match self.value {
+            None => Ok(true),
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Bool(ref b),
+                ..
+            })) => Ok(b.value),
+            Some(ref value) => {
+                let msg = format!("expected `true` or `false` here for {}", self.ident);
+                Err(syn::Error::new_spanned(value, msg))
+            }
+        }
+    }
+
+    fn name(&self) -> syn::Result<syn::Ident> {
+        expr_ident(self.value()?)
+            .ok_or_else(|| self.error(format_args!("{} requires a name", self.ident)))
+    }
+
+    /// a path given either as it is or in a string
+    fn path(&self) -> syn::Result<syn::Expr> {
+        match expr_str_raw(self.value()?) {
+            Some(_) => self.parse_str("a path"),
+            None => self.value().cloned(),
+        }
+    }
+
+    fn parse_str<T: Parse>(&self, what: &str) -> syn::Result<T> {
+        syn::parse_str(&self.str()?).map_err(|e| {
+            syn::Error::new_spanned(&self.value, format_args!("could not parse {what}: {e}"))
+        })
+    }
+}
+
+impl quote::ToTokens for AttrArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self { ident, value, list } = self;
+        let list = list.iter();
+        let value = value.iter();
+        tokens.extend(quote!(#ident #((#list))* #(= #value)*));
+    }
+}
+
+struct Subset {
+    ident: syn::Ident,
+    variants: Punctuated<syn::Ident, Token![,]>,
+}
+
+impl Parse for Subset {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let variants = Punctuated::parse_terminated(input)?;
+        Ok(Self { ident, variants })
+    }
+}
+
+struct AttrArgs(Vec<AttrArg>);
+
+impl Parse for AttrArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = vec![];
+        loop {
+            args.push(input.parse()?);
+            if input.parse::<Token![,]>().is_err() {
+                return Ok(Self(args));
+            }
+        }
+    }
+}
+
+fn parse_attr_doc(a: &syn::Attribute) -> Option<&syn::Expr> {
+    if !matches!(a.style, syn::AttrStyle::Outer) {
+        return None;
+    }
+    let syn::Meta::NameValue(ref nameval) = a.meta else {
+        return None;
+    };
+    if !nameval.path.is_ident("doc") {
+        return None;
+    }
+    Some(&nameval.value)
+}
+
+fn parse_attr(a: &syn::Attribute) -> Option<syn::Result<AttrArgs>> {
+    if !matches!(a.style, syn::AttrStyle::Outer) {
+        return None;
+    }
+    let syn::Meta::List(ref list) = a.meta else {
+        return None;
+    };
+    if !matches!(list.delimiter, syn::MacroDelimiter::Paren(_)) {
+        return None;
+    }
+    if !list.path.is_ident("err") {
+        return None;
+    }
+    Some(list.parse_args())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Level {
+    Enum,
+    Variant,
+    Field,
+}
+
+impl Level {
+    const ALL: [Self; 3] = [Self::Enum, Self::Variant, Self::Field];
+
+    fn keys(self) -> &'static [&'static str] {
+        match self {
+            Self::Enum => &[
+                "additive",
+                "allow_unknown_attrs",
+                "anyhow",
+                "assert",
+                "boxed",
+                "bullet",
+                "color",
+                "context_chain",
+                "crate",
+                "deny_duplicate_messages",
+                "display_fields",
+                "display_variant_name",
+                "error_kind",
+                "error_meta",
+                "error_trait",
+                "field_list_style",
+                "hash_by_code",
+                "impl_exit_code",
+                "impl_from_display",
+                "implicit_location",
+                "infallible_from",
+                "into",
+                "machine_readable",
+                "message_consts",
+                "module",
+                "module_vis",
+                "msg_doc",
+                "on_display",
+                "opaque",
+                "opaque_vis",
+                "pretty",
+                "quote_values",
+                "require_messages",
+                "result_alias",
+                "serde",
+                "static_table",
+                "std_error_v2",
+                "strict",
+                "strip_messages",
+                "subset",
+                "template",
+                "terminator",
+                "tracing_event",
+                "track_caller",
+                "traits_crate",
+                "variant_index",
+                "variants_count",
+                "vis",
+                "with",
+                "with_depth",
+                "write_to",
+            ],
+            Self::Variant => &[
+                "as_static",
+                "code",
+                "const_default_msg",
+                "exit_code",
+                "feature",
+                "fmt_if",
+                "from",
+                "into",
+                "key",
+                "message_prefix_field",
+                "msg",
+                "no_terminator",
+                "other",
+                "severity",
+                "template",
+                "tracing_level",
+                "transparent",
+            ],
+            Self::Field => &[
+                "bool",
+                "bullet",
+                "debug",
+                "duration",
+                "field_list_style",
+                "implicit",
+                "list",
+                "path",
+                "quote",
+                "skip",
+                "source",
+                "source_debug",
+                "via",
+            ],
+        }
+    }
+
+    fn accepts(self, key: &str) -> bool {
+        let method_vis = key
+            .strip_suffix("_vis")
+            .is_some_and(|m| MethodVis::METHODS.contains(&m));
+        self.keys().contains(&key) || (self == Self::Enum && method_vis)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Enum => "the enum",
+            Self::Variant => "variants",
+            Self::Field => "fields",
+        }
+    }
+}
+
+/// optimal string alignment distance, for suggesting similar keys
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<_>, Vec<_>) = (a.chars().collect(), b.chars().collect());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+fn check_keys(args: &[AttrArg], level: Level) -> syn::Result<()> {
+    let mut errors = vec![];
+    for a in args {
+        let key = a.ident.to_string();
+        if level.accepts(&key) {
+            continue;
+        }
+        let elsewhere: Vec<_> = Level::ALL
+            .into_iter()
+            .filter(|l| l.accepts(&key))
+            .map(Level::name)
+            .collect();
+        let msg = if !elsewhere.is_empty() {
+            format!("`{key}` can only be used on {}", elsewhere.join(" or "))
+        } else {
+            let similar = level
+                .keys()
+                .iter()
+                .map(|k| (edit_distance(&key, k), k))
+                .filter(|&(d, _)| d <= key.len().div_ceil(2))
+                .min();
+            match similar {
+                Some((_, k)) => format!("unknown argument `{key}`, did you mean `{k}`?"),
+                None => format!("unknown argument `{key}`"),
+            }
+        };
+        errors.push(syn::Error::new_spanned(&a.ident, msg));
+    }
+    // all of a variant's or field's attributes are merged, and a value given
+    // twice would otherwise quietly lose to the last one. messages are
+    // checked by check_msg_conflicts
+    const REPEATABLE: &[&str] = &["assert", "subset", "msg", "const_default_msg"];
+    for (i, a) in args.iter().enumerate() {
+        if a.value.is_none() || REPEATABLE.iter().any(|k| a.ident == k) {
+            continue;
+        }
+        if args[..i].iter().any(|p| p.ident == a.ident) {
+            let msg = format!("`{}` is given more than once", a.ident);
+            errors.push(syn::Error::new_spanned(&a.ident, msg));
+        }
+    }
+    combine(errors).map_or(Ok(()), Err)
+}
+
+fn combine(errors: impl IntoIterator<Item = syn::Error>) -> Option<syn::Error> {
+    errors.into_iter().reduce(|mut a, b| {
+        a.combine(b);
+        a
+    })
+}
+
+/// like collecting into a `Result`, but keeps every error instead of the first
+fn collect_all<T>(items: impl Iterator<Item = syn::Result<T>>) -> syn::Result<Vec<T>> {
+    let mut ok = vec![];
+    let mut errors = vec![];
+    for item in items {
+        match item {
+            Ok(item) => ok.push(item),
+            Err(err) => errors.push(err),
+        }
+    }
+    combine(errors).map_or(Ok(ok), Err)
+}
+
+/// turn `#[err(feature = "...")]` on a variant into `#[cfg(feature = "...")]`,
+/// which only works before deriving since a derive can't remove variants
+fn gate_variant(v: &mut syn::Variant) -> syn::Result<()> {
+    let mut attrs = vec![];
+    for attr in std::mem::take(&mut v.attrs) {
+        let Some(args) = parse_attr(&attr) else {
+            attrs.push(attr);
+            continue;
+        };
+        let (features, rest): (Vec<_>, Vec<_>) =
+            args?.0.into_iter().partition(|a| a.ident == "feature");
+        for a in features {
+            let feature = syn::LitStr::new(&a.str()?, a.value()?.span());
+            attrs.push(syn::parse_quote!(#[cfg(feature = #feature)]));
+        }
+        if !rest.is_empty() {
+            attrs.push(syn::parse_quote!(#[err(#(#rest),*)]));
+        }
+    }
+    v.attrs = attrs;
+    Ok(())
+}
+
+/// whether any level accepts an argument, for `#[err(allow_unknown_attrs)]`
+fn known_key(a: &AttrArg) -> bool {
+    let key = a.ident.to_string();
+    Level::ALL.into_iter().any(|l| l.accepts(&key))
+}
+
+/// drop the arguments no level accepts from a variant and its fields, so
+/// that ones meant for newer versions are ignored instead of rejected
+fn drop_unknown(v: &mut syn::Variant) -> syn::Result<()> {
+    let fields = v.fields.iter_mut().map(|f| &mut f.attrs);
+    for attrs in std::iter::once(&mut v.attrs).chain(fields) {
+        for attr in std::mem::take(attrs) {
+            let Some(args) = parse_attr(&attr) else {
+                attrs.push(attr);
+                continue;
+            };
+            let args: Vec<_> = args?.0.into_iter().filter(known_key).collect();
+            if !args.is_empty() {
+                attrs.push(syn::parse_quote!(#[err(#(#args),*)]));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_attrs(attrs: &[syn::Attribute]) -> syn::Result<Vec<AttrArg>> {
+    let mut args = vec![];
+    for a in attrs.iter().filter_map(parse_attr) {
+        args.extend(a?.0);
+    }
+    Ok(args)
+}
+
+fn expr_str(a: &syn::Expr) -> Option<String> {
+    expr_str_raw(a).map(|mut s| {
+        if s.starts_with(' ') {
+            s.remove(0);
+        }
+        s
+    })
+}
+
+fn expr_str_raw(a: &syn::Expr) -> Option<String> {
+    match a {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(s),
+            ..
+        }) => Some(s.value()),
+        _ => None,
+    }
+}
+
+fn is_numeric(ty: &syn::Type) -> bool {
+    const NUMERIC: &[&str] = &[
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+        "f32", "f64",
+    ];
+    let syn::Type::Path(syn::TypePath { ref path, .. }) = ty else {
+        return false;
+    };
+    path.get_ident()
+        .is_some_and(|i| NUMERIC.iter().any(|n| i == n))
+}
+
+fn parse_field(f: syn::Field, quote_values: bool, list_style: &ListStyle) -> syn::Result<Field> {
+    let args = parse_attrs(&f.attrs)?;
+    check_keys(&args, Level::Field)?;
+    let source_debug = arg_flag(&args, "source_debug")?;
+    let field_list_style = ListStyle::parse(&args)?;
+    let list = arg_flag(&args, "list")? || field_list_style.bullets.is_some();
+    let duration = arg_flag(&args, "duration")?;
+    let path = arg_flag(&args, "path")?;
+    let debug = arg_flag(&args, "debug")? || source_debug;
+    let bool_words = args
+        .iter()
+        .rfind(|a| a.ident == "bool")
+        .map(|a| match a.str()?.split_once('/') {
+            Some((yes, no)) if !no.contains('/') => Ok((yes.to_string(), no.to_string())),
+            _ => Err(a.error("bool must look like \"yes/no\"")),
+        })
+        .transpose()?;
+    let via = args
+        .iter()
+        .rfind(|a| a.ident == "via")
+        .map(AttrArg::path)
+        .transpose()?;
+    let render = if let Some(adapter) = via {
+        Render::Via(adapter)
+    } else if let Some((yes, no)) = bool_words {
+        Render::Bool(yes, no)
+    } else if duration {
+        Render::Duration
+    } else if path {
+        Render::Path
+    } else if list {
+        let ListStyle { bullets, bullet } = field_list_style.or(list_style);
+        let bullet = bullet.unwrap_or_else(|| "• ".to_string());
+        Render::List(bullets.unwrap_or_default().then_some(bullet))
+    } else if debug {
+        Render::Debug
+    } else {
+        Render::Display
+    };
+    let quote = (arg_flag(&args, "quote")? || quote_values)
+        && !is_numeric(&f.ty)
+        && matches!(
+            render,
+            Render::Display | Render::Duration | Render::Path | Render::Via(_)
+        );
+    let source = arg_flag(&args, "source")? || source_debug;
+    let skip = arg_flag(&args, "skip")?;
+    let implicit = args
+        .iter()
+        .rev()
+        .find(|a| a.ident == "implicit")
+        .map(|a| match a.value {
+            Some(ref init) => quote!(#init),
+            None => quote!(::core::default::Default::default()),
+        });
+    Ok(Field {
+        ident: f.ident,
+        span: f.ty.span(),
+        ty: f.ty,
+        quote,
+        source,
+        skip,
+        prefix: false,
+        implicit,
+        render,
+    })
+}
+
+#[derive(Clone)]
+enum TemplatePart {
+    Lit(String),
+    Msg,
+    Fields,
+}
+
+fn parse_template(tpl: &str, arg: &AttrArg) -> syn::Result<Vec<TemplatePart>> {
+    let mut parts = vec![];
+    let mut lit = String::new();
+    let mut rest = tpl;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("{{") {
+            lit.push('{');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("}}") {
+            lit.push('}');
+            rest = after;
+        } else if c == '{' {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| arg.error("unclosed { in template"))?;
+            let part = match &rest[1..end] {
+                "msg" => TemplatePart::Msg,
+                "fields" => TemplatePart::Fields,
+                other => {
+                    return Err(arg.error(format_args!(
+                        "unknown template token {{{other}}}, expected {{msg}} or {{fields}}"
+                    )))
+                }
+            };
+            if !lit.is_empty() {
+                parts.push(TemplatePart::Lit(std::mem::take(&mut lit)));
+            }
+            parts.push(part);
+            rest = &rest[end + 1..];
+        } else {
+            lit.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    if !lit.is_empty() {
+        parts.push(TemplatePart::Lit(lit));
+    }
+    Ok(parts)
+}
+
+fn arg_template(args: &[AttrArg]) -> syn::Result<Option<Vec<TemplatePart>>> {
+    args.iter()
+        .rfind(|a| a.ident == "template")
+        .map(|a| parse_template(&a.str()?, a))
+        .transpose()
+}
+
+/// a doc comment is a fallback and can be overridden, but a message given
+/// twice or alongside a forwarded one is probably a mistake
+fn check_msg_conflicts(args: &[AttrArg]) -> syn::Result<()> {
+    let mut first: Option<&AttrArg> = None;
+    for a in args {
+        let forwards = (a.ident == "transparent" || a.ident == "other") && a.flag()?;
+        let is_msg = |a: &AttrArg| a.ident == "msg" || a.ident == "const_default_msg";
+        if !is_msg(a) && !forwards {
+            continue;
+        }
+        let Some(prev) = first else {
+            first = Some(a);
+            continue;
+        };
+        let msg = match (is_msg(prev), is_msg(a)) {
+            (true, true) => "msg is given more than once".to_string(),
+            (false, false) => continue,
+            _ => format!(
+                "`{}` conflicts with `{}`, which uses the inner error's message",
+                if forwards { &prev.ident } else { &a.ident },
+                if forwards { &a.ident } else { &prev.ident }
+            ),
+        };
+        return Err(syn::Error::new_spanned(&a.ident, msg));
+    }
+    Ok(())
+}
+
+/// make sure every `{field}` in a message names one of the variant's fields,
+/// since anything that looks like a placeholder but isn't would otherwise
+/// silently show up as-is
+fn check_placeholders(
+    msg: &str,
+    v: &syn::Ident,
+    style: &Style,
+    fields: &[Field],
+    lit: &syn::Expr,
+) -> syn::Result<()> {
+    let mut names: Vec<_> = fields
+        .iter()
+        .enumerate()
+        .map(|(fnum, f)| match f.ident {
+            Some(ref fnm) => fnm.to_string(),
+            None => fnum.to_string(),
+        })
+        .collect();
+    if fields.iter().any(|f| f.source) && !names.iter().any(|n| n == "source") {
+        names.push("source".to_string());
+    }
+    let mut errors = vec![];
+    let mut rest = msg;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        if let Some(after) = after.strip_prefix('{') {
+            rest = after;
+            continue;
+        }
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = &after[..end];
+        let index = !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit());
+        let ident = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !index && !ident {
+            rest = after;
+            continue;
+        }
+        rest = &after[end + 1..];
+        if names.iter().any(|n| n == name) {
+            continue;
+        }
+        let fields = match names.len() {
+            0 => format!("{v} has no fields"),
+            1 => format!("{v} only has `{{{}}}`", names[0]),
+            _ => {
+                let names: Vec<_> = names.iter().map(|n| format!("`{{{n}}}`")).collect();
+                format!("{v} has {}", names.join(", "))
+            }
+        };
+        let msg = match (style, index) {
+            (Style::Named, true) => {
+                format!("`{{{name}}}` refers to a field by position, but {fields}")
+            }
+            (Style::Unnamed, false) if name != "source" => {
+                format!("`{{{name}}}` refers to a field by name, but {fields}")
+            }
+            (Style::Unnamed, true) => format!("`{{{name}}}` is out of range, {fields}"),
+            _ => {
+                let similar = names
+                    .iter()
+                    .map(|n| (edit_distance(name, n), n))
+                    .filter(|&(d, _)| d <= name.len().div_ceil(2))
+                    .min();
+                match similar {
+                    Some((_, n)) => format!("no field named `{name}`, did you mean `{{{n}}}`?"),
+                    None => format!("no field named `{name}`, {fields}"),
+                }
+            }
+        };
+        errors.push(syn::Error::new_spanned(lit, msg));
+    }
+    combine(errors).map_or(Ok(()), Err)
+}
+
+fn parse_variant(
+    v: syn::Variant,
+    ty: &syn::Ident,
+    quote_values: bool,
+    template: Option<&Vec<TemplatePart>>,
+    terminator: Option<&String>,
+    list_style: &ListStyle,
+    msg_doc: MsgDoc,
+) -> syn::Result<Variant> {
+    let docs: Vec<_> = v.attrs.iter().filter_map(parse_attr_doc).collect();
+    let doc = docs.first().copied();
+    let discriminant = v.discriminant.as_ref().map(|(_, expr)| expr.clone());
+    let doc_msg = || {
+        let mut lines = docs.iter().filter_map(|d| expr_str(d));
+        match msg_doc {
+            MsgDoc::First => lines.next(),
+            MsgDoc::Paragraph => Some(
+                lines
+                    .take_while(|l| !l.trim().is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+            .filter(|m| !m.is_empty()),
+            MsgDoc::All => Some(lines.collect::<Vec<_>>().join("\n")).filter(|m| !m.is_empty()),
+        }
+    };
+    let args = parse_attrs(&v.attrs)?;
+    check_keys(&args, Level::Variant)?;
+    check_msg_conflicts(&args)?;
+    if let Some(a) = args.iter().find(|a| a.ident == "feature") {
+        return Err(a.error(
+            "feature needs #[foxerror::error] or fox! to remove the variant, use #[cfg(feature = ...)] with the derive",
+        ));
+    }
+    let expand = |m: String| match m.contains('{') {
+        true => Msg::Str(
+            m.replace("{type}", &ty.to_string())
+                .replace("{variant}", &unraw(&v.ident)),
+        ),
+        false => Msg::Str(m),
+    };
+    let amsg = args.iter().find(|a| a.ident == "msg");
+    let const_msg = args.iter().find(|a| a.ident == "const_default_msg");
+    let msg = match amsg.map(|a| (a, a.value.as_ref())) {
+        // string-producing macros like concat! are used as they are
+        Some((_, Some(syn::Expr::Macro(mac)))) => Some(Msg::Macro(mac.clone())),
+        // and so are constants defined elsewhere
+        Some((_, Some(syn::Expr::Path(path)))) => Some(Msg::Const(syn::Expr::Path(path.clone()))),
+        Some((a, value)) => {
+            let msg = value.and_then(expr_str).ok_or_else(|| {
+                let msg =
+                    "expected a string literal, a constant or a macro like concat! here for msg";
+                match value {
+                    Some(value) => syn::Error::new_spanned(value, msg),
+                    None => a.error(msg),
+                }
+            })?;
+            Some(expand(msg))
+        }
+        None => match const_msg {
+            Some(a) => Some(Msg::Const(a.path()?)),
+            None => doc_msg().map(expand),
+        },
+    };
+    // catch-all variants forward everything to the error they hold
+    let other = arg_flag(&args, "other")?;
+    let from = arg_flag(&args, "from")? || other;
+    let transparent = arg_flag(&args, "transparent")? || other;
+    let no_terminator = arg_flag(&args, "no_terminator")?;
+    let terminator = terminator.filter(|_| !no_terminator).cloned();
+    let exit_code = args
+        .iter()
+        .rev()
+        .find(|a| a.ident == "exit_code")
+        .map(|a| {
+            let value = a.value()?;
+            // anything else would wrap around, possibly to 0, meaning success
+            let in_range = match value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(i),
+                    ..
+                }) => i.base10_parse::<u8>().is_ok_and(|c| c != 0),
+                syn::Expr::Unary(syn::ExprUnary {
+                    op: syn::UnOp::Neg(_),
+                    expr,
+                    ..
+                }) => !matches!(**expr, syn::Expr::Lit(_)),
+                _ => true,
+            };
+            match in_range {
+                true => Ok(value.clone()),
+                false => Err(syn::Error::new_spanned(
+                    value,
+                    "exit_code must be within 1..=255",
+                )),
+            }
+        })
+        .transpose()?;
+    let code = args
+        .iter()
+        .rev()
+        .find(|a| a.ident == "code")
+        .map(|a| a.value().cloned())
+        .transpose()?;
+    let template = arg_template(&args)?.or_else(|| template.cloned());
+    let into = args
+        .iter()
+        .rfind(|a| a.ident == "into")
+        .map(AttrArg::path)
+        .transpose()?;
+    let severity = args
+        .iter()
+        .rfind(|a| a.ident == "severity")
+        .map(|a| {
+            let severity = match a.str()?.as_str() {
+                "warning" => "Warning",
+                "error" => "Error",
+                "fatal" => "Fatal",
+                _ => return Err(a.error("severity must be one of warning, error or fatal")),
+            };
+            Ok(syn::Ident::new(severity, Span::call_site()))
+        })
+        .transpose()?;
+    let fmt_if = args
+        .iter()
+        .rfind(|a| a.ident == "fmt_if")
+        .map(|a| a.value().cloned())
+        .transpose()?;
+    let tracing_level = arg_tracing_level(&args, "tracing_level")?;
+    let as_static = arg_flag(&args, "as_static")?;
+    let key = args
+        .iter()
+        .rfind(|a| a.ident == "key")
+        .map(AttrArg::str)
+        .transpose()?;
+    let style = match v.fields {
+        syn::Fields::Named(_) => Style::Named,
+        syn::Fields::Unnamed(_) => Style::Unnamed,
+        syn::Fields::Unit => Style::Unit,
+    };
+    let fields = v
+        .fields
+        .into_iter()
+        .map(|f| parse_field(f, quote_values, list_style));
+    let mut fields = collect_all(fields)?;
+    let prefix_field = match args.iter().rfind(|a| a.ident == "message_prefix_field") {
+        Some(a) => {
+            let name = a.str()?;
+            let Some(fnum) = fields
+                .iter()
+                .position(|f| f.ident.as_ref().is_some_and(|i| *i == name))
+            else {
+                let msg = format!("no field named `{name}` to put before the message");
+                return Err(a.error(msg));
+            };
+            fields[fnum].prefix = true;
+            Some(fnum)
+        }
+        None => None,
+    };
+    // unit variants have nothing to refer to, which makes any placeholder in
+    // their messages a mistake
+    let lit = amsg.and_then(|a| a.value.as_ref()).or(doc);
+    if let (Some(Msg::Str(ref msg)), Some(lit)) = (&msg, lit) {
+        check_placeholders(msg, &v.ident, &style, &fields, lit)?;
+    }
+    Ok(Variant {
+        ident: v.ident,
+        style,
+        fields,
+        msg,
+        prefix_field,
+        from,
+        transparent,
+        other,
+        exit_code,
+        code,
+        template,
+        terminator,
+        into,
+        severity,
+        fmt_if,
+        tracing_level,
+        as_static,
+        key,
+        discriminant,
+        def: None,
+    })
+}
+
+fn arg_tracing_level(args: &[AttrArg], name: &str) -> syn::Result<Option<syn::Ident>> {
+    let Some(a) = args.iter().rfind(|a| a.ident == name) else {
+        return Ok(None);
+    };
+    if !cfg!(feature = "tracing") {
+        let msg = format!("the tracing feature of foxerror must be enabled to use #[err({name})]");
+        return Err(a.error(msg));
+    }
+    let level = match a.str()?.as_str() {
+        "trace" => "TRACE",
+        "debug" => "DEBUG",
+        "info" => "INFO",
+        "warn" => "WARN",
+        "error" => "ERROR",
+        _ => {
+            return Err(a.error(format_args!(
+                "{name} must be one of trace, debug, info, warn or error"
+            )))
+        }
+    };
+    Ok(Some(syn::Ident::new(level, Span::call_site())))
+}
+
+fn arg_flag(args: &[AttrArg], name: &str) -> syn::Result<bool> {
+    args.iter()
+        .rfind(|a| a.ident == name)
+        .map_or(Ok(false), AttrArg::flag)
+}
+
+fn expr_ident(a: &syn::Expr) -> Option<syn::Ident> {
+    match a {
+        syn::Expr::Path(syn::ExprPath { path, .. }) => path.get_ident().cloned(),
+        _ => None,
+    }
+}
+
+fn parse_derive(ast: DeriveInput) -> syn::Result<ParsedErrors> {
+    let ident = ast.ident;
+    let vis = ast.vis;
+    let generics = ast.generics;
+    let mut args = parse_attrs(&ast.attrs)?;
+    let allow_unknown = arg_flag(&args, "allow_unknown_attrs")?;
+    if allow_unknown {
+        args.retain(known_key);
+    }
+    check_keys(&args, Level::Enum)?;
+    let quote_values = arg_flag(&args, "quote_values")?;
+    let template = arg_template(&args)?;
+    let list_style = ListStyle::parse(&args)?;
+    let terminator = args
+        .iter()
+        .rfind(|a| a.ident == "terminator")
+        .map(AttrArg::str)
+        .transpose()?;
+    let mut asserts: Vec<_> = args
+        .iter()
+        .filter(|a| a.ident == "assert")
+        .map(|a| {
+            let bounds = a.str()?;
+            let parser = Punctuated::<syn::TypeParamBound, Token![+]>::parse_separated_nonempty;
+            let bounds = parser
+                .parse_str(&bounds)
+                .map_err(|e| a.error(format_args!("could not parse assert bounds: {e}")))?;
+            Ok(quote!(#bounds))
+        })
+        .collect::<syn::Result<_>>()?;
+    let anyhow = arg_flag(&args, "anyhow")?;
+    if let Some(a) = args
+        .iter()
+        .rfind(|a| a.ident == "anyhow")
+        .filter(|_| anyhow)
+    {
+        if !cfg!(feature = "anyhow") {
+            return Err(
+                a.error("the anyhow feature of foxerror must be enabled to use #[err(anyhow)]")
+            );
+        }
+        asserts.push(quote!(
+            ::core::marker::Send + ::core::marker::Sync + 'static
+        ));
+    }
+    let std_error_v2 = arg_flag(&args, "std_error_v2")?;
+    if let Some(a) = args
+        .iter()
+        .rfind(|a| a.ident == "std_error_v2")
+        .filter(|_| std_error_v2 && !cfg!(feature = "nightly"))
+    {
+        return Err(
+            a.error("the nightly feature of foxerror must be enabled to use #[err(std_error_v2)]")
+        );
+    }
+    let impl_exit_code = arg_flag(&args, "impl_exit_code")?;
+    let impl_from_display = args
+        .iter()
+        .rfind(|a| a.ident == "impl_from_display")
+        .map(|a| match a.value {
+            Some(_) => a.name(),
+            None => Ok(syn::Ident::new(&format!("Parse{ident}Error"), ident.span())),
+        })
+        .transpose()?;
+    let track_caller = arg_flag(&args, "track_caller")?;
+    let result_alias = args
+        .iter()
+        .rfind(|a| a.ident == "result_alias")
+        .map(|a| match a.value {
+            Some(_) => a.name(),
+            None => Ok(syn::Ident::new("Result", Span::call_site())),
+        })
+        .transpose()?;
+    let context_chain = arg_flag(&args, "context_chain")?;
+    let with_depth = arg_flag(&args, "with_depth")?;
+    let display_fields = arg_flag(&args, "display_fields")?;
+    let variant_index = arg_flag(&args, "variant_index")?;
+    let variants_count = arg_flag(&args, "variants_count")?;
+    let deny_duplicate_messages = arg_flag(&args, "deny_duplicate_messages")?;
+    let machine_readable = arg_flag(&args, "machine_readable")?;
+    let serde = arg_flag(&args, "serde")?;
+    let write_to = arg_flag(&args, "write_to")?;
+    let message_consts = arg_flag(&args, "message_consts")?;
+    if let Some(a) = args
+        .iter()
+        .rfind(|a| a.ident == "message_consts")
+        .filter(|_| message_consts && generics.type_params().next().is_some())
+    {
+        // message! names the enum without its parameters, so they couldn't be inferred
+        return Err(a.error("message_consts can't be used on enums with type parameters"));
+    }
+    if let Some(a) = args
+        .iter()
+        .rfind(|a| a.ident == "serde")
+        .filter(|_| serde && !cfg!(feature = "serde"))
+    {
+        return Err(a.error("the serde feature of foxerror must be enabled to use #[err(serde)]"));
+    }
+    let infallible_from = arg_flag(&args, "infallible_from")?;
+    let strict = arg_flag(&args, "strict")? || arg_flag(&args, "require_messages")?;
+    let error_kind = arg_flag(&args, "error_kind")?;
+    let error_meta = arg_flag(&args, "error_meta")?;
+    let additive = arg_flag(&args, "additive")?;
+    let static_table = arg_flag(&args, "static_table")?;
+    let color = arg_flag(&args, "color")?;
+    let pretty = arg_flag(&args, "pretty")?;
+    let method_vis = MethodVis::parse(&args)?;
+    let tracing_event = arg_tracing_level(&args, "tracing_event")?;
+    if let Some(a) = args
+        .iter()
+        .rfind(|a| a.ident == "color")
+        .filter(|_| color && !cfg!(feature = "color"))
+    {
+        return Err(a.error("the color feature of foxerror must be enabled to use #[err(color)]"));
+    }
+    let boxed = args
+        .iter()
+        .rfind(|a| a.ident == "boxed")
+        .map(AttrArg::name)
+        .transpose()?;
+    let error_trait = args
+        .iter()
+        .rfind(|a| a.ident == "error_trait")
+        .map(|a| match a.path()? {
+            syn::Expr::Path(p) if p.qself.is_none() => Ok(p.path),
+            _ => Err(a.error("error_trait must be a path to a trait")),
+        })
+        .transpose()?;
+    // these all hand the enum out as a core::error::Error
+    const NEEDS_ERROR: &[&str] = &[
+        "anyhow",
+        "boxed",
+        "context_chain",
+        "impl_from_display",
+        "opaque",
+        "std_error_v2",
+        "with_depth",
+    ];
+    if let (Some(_), Some(a)) = (
+        &error_trait,
+        args.iter().find(|a| {
+            // a flag turned off doesn't need anything
+            NEEDS_ERROR.iter().any(|n| a.ident == n) && !matches!(a.flag(), Ok(false))
+        }),
+    ) {
+        let msg = format!(
+            "{} needs core::error::Error, which error_trait replaces",
+            a.ident
+        );
+        return Err(a.error(msg));
+    }
+    let strip_messages = args
+        .iter()
+        .rfind(|a| a.ident == "strip_messages")
+        .map(|a| {
+            a.list.clone().ok_or_else(|| {
+                a.error(
+                    "strip_messages needs a cfg predicate, like strip_messages(feature = \"tiny\")",
+                )
+            })
+        })
+        .transpose()?;
+    let on_display = args
+        .iter()
+        .rfind(|a| a.ident == "on_display")
+        .map(|a| a.parse_str("a function path"))
+        .transpose()?;
+    let hash_by_code = arg_flag(&args, "hash_by_code")?;
+    let variant_name = match args.iter().rfind(|a| a.ident == "display_variant_name") {
+        None => VariantName::Auto,
+        Some(a) => match a.str()?.as_str() {
+            "auto" => VariantName::Auto,
+            "always" => VariantName::Always,
+            "never" => VariantName::Never,
+            _ => return Err(a.error("display_variant_name must be one of auto, always or never")),
+        },
+    };
+    let msg_doc = match args.iter().rfind(|a| a.ident == "msg_doc") {
+        None => MsgDoc::First,
+        Some(a) => match a.str()?.as_str() {
+            "first" => MsgDoc::First,
+            "paragraph" => MsgDoc::Paragraph,
+            "all" => MsgDoc::All,
+            _ => return Err(a.error("msg_doc must be one of first, paragraph or all")),
+        },
+    };
+    let module = args
+        .iter()
+        .rev()
+        .find(|a| a.ident == "module")
+        .map(|a| {
+            let name = a.name()?;
+            let vis = match args.iter().rev().find(|a| a.ident == "module_vis") {
+                Some(a) => a.parse_str("a visibility")?,
+                None => vis.clone(),
+            };
+            syn::Result::Ok((vis, name))
+        })
+        .transpose()?;
+    let opaque = args
+        .iter()
+        .rfind(|a| a.ident == "opaque")
+        .map(|a| {
+            let name = a.name()?;
+            let vis = match args.iter().rfind(|a| a.ident == "opaque_vis") {
+                Some(a) => a.parse_str("a visibility")?,
+                None => syn::parse_quote!(pub),
+            };
+            syn::Result::Ok((vis, name))
+        })
+        .transpose()?;
+    let into = args
+        .iter()
+        .rfind(|a| a.ident == "into")
+        .map(AttrArg::path)
+        .transpose()?;
+    let with = args
+        .iter()
+        .rfind(|a| a.ident == "with")
+        .map(|a| match into {
+            Some(_) => a.path(),
+            None => Err(a.error("with requires into")),
+        })
+        .transpose()?;
+    let into = into.map(|into| (into, with));
+    let subsets: Vec<Subset> = args
+        .iter()
+        .filter(|a| a.ident == "subset")
+        .map(|a| {
+            let list = a
+                .list
+                .as_ref()
+                .ok_or_else(|| a.error("subset requires a name and variants"))?;
+            syn::parse2(list.clone())
+        })
+        .collect::<syn::Result<_>>()?;
+    let krate = crate_path(&ast.attrs)?;
+    let traits_crate = traits_path(&args)?;
+    // enum-level arguments that should apply to subsets as well
+    const SUBSET_ARGS: &[&str] = &[
+        "quote_values",
+        "template",
+        "terminator",
+        "field_list_style",
+        "bullet",
+        "display_variant_name",
+        "msg_doc",
+        "context_chain",
+        "with_depth",
+        "crate",
+        "traits_crate",
+        "error_trait",
+        "vis",
+        "write_to",
+        "message_consts",
+    ];
+    let subset_args: Vec<_> = args
+        .into_iter()
+        .filter(|a| SUBSET_ARGS.iter().any(|n| a.ident == n))
+        .collect();
+    let body = match ast.data {
+        syn::Data::Enum(body) => body,
+        syn::Data::Struct(s) => {
+            return Err(syn::Error::new_spanned(
+                s.struct_token,
+                "only enums are supported",
+            ))
+        }
+        syn::Data::Union(u) => {
+            return Err(syn::Error::new_spanned(
+                u.union_token,
+                "only enums are supported",
+            ))
+        }
+    };
+    let keep_defs = !subsets.is_empty();
+    let variants = body.variants.into_iter().map(|mut v| {
+        if allow_unknown {
+            drop_unknown(&mut v)?;
+        }
+        let def = keep_defs.then(|| v.clone());
+        parse_variant(
+            v,
+            &ident,
+            quote_values,
+            template.as_ref(),
+            terminator.as_ref(),
+            &list_style,
+            msg_doc,
+        )
+        .map(|v| Variant { def, ..v })
+    });
+    let variants = collect_all(variants)?;
+
+    Ok(ParsedErrors {
+        ident,
+        generics,
+        variants,
+        asserts,
+        impl_exit_code,
+        impl_from_display,
+        module,
+        context_chain,
+        with_depth,
+        display_fields,
+        variant_index,
+        variants_count,
+        deny_duplicate_messages,
+        hash_by_code,
+        variant_name,
+        std_error_v2,
+        vis,
+        result_alias,
+        machine_readable,
+        serde,
+        write_to,
+        message_consts,
+        subsets,
+        subset_args,
+        krate,
+        traits_crate,
+        opaque,
+        into,
+        infallible_from,
+        track_caller,
+        on_display,
+        strict,
+        boxed,
+        error_trait,
+        error_kind,
+        error_meta,
+        additive,
+        static_table,
+        color,
+        pretty,
+        method_vis,
+        tracing_event,
+        strip_messages,
+    })
+}
+
+fn generate_helpers(variants: &[Variant]) -> TokenStream {
+    let has_render = |render: fn(&Render) -> bool| {
+        variants
+            .iter()
+            .flat_map(|v| &v.fields)
+            .any(|f| render(&f.render))
+    };
+    let mut helpers = quote!();
+
+    if has_render(|r| matches!(r, Render::Debug)) {
+        helpers.extend(quote! {
+            struct __FoxDebug<'a, T: ?::core::marker::Sized>(&'a T);
+
+            impl<T: ::core::fmt::Debug + ?::core::marker::Sized> ::core::fmt::Display for __FoxDebug<'_, T> {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Debug::fmt(self.0, f)
+                }
+            }
+        });
+    }
+    if has_render(|r| matches!(r, Render::List(_))) {
+        helpers.extend(quote! {
+            struct __FoxList<'a, T: ?::core::marker::Sized>(&'a T, ::core::option::Option<&'static str>);
+
+            impl<'a, T: ?::core::marker::Sized> ::core::fmt::Display for __FoxList<'a, T>
+            where
+                &'a T: ::core::iter::IntoIterator,
+                <&'a T as ::core::iter::IntoIterator>::Item: ::core::fmt::Display,
+            {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    let ::core::option::Option::Some(bullet) = self.1 else {
+                        f.write_str("[")?;
+                        let items = ::core::iter::IntoIterator::into_iter(self.0);
+                        for (i, item) in ::core::iter::Iterator::enumerate(items) {
+                            if i > 0 {
+                                f.write_str(", ")?;
+                            }
+                            ::core::write!(f, "{}", item)?;
+                        }
+                        return f.write_str("]");
+                    };
+                    for item in self.0 {
+                        ::core::write!(f, "\n{}{}", bullet, item)?;
+                    }
+                    ::core::result::Result::Ok(())
+                }
+            }
+        });
+    }
+    if has_render(|r| matches!(r, Render::Duration)) {
+        helpers.extend(quote! {
+            struct __FoxDuration<'a>(&'a ::core::time::Duration);
+
+            impl ::core::fmt::Display for __FoxDuration<'_> {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    if self.0.as_secs() > 0 {
+                        ::core::write!(f, "{}s", self.0.as_millis() as f64 / 1000.0)
+                    } else {
+                        ::core::write!(f, "{}ms", self.0.as_micros() as f64 / 1000.0)
+                    }
+                }
+            }
+        });
+    }
+
+    helpers
+}
+
+fn generate_assert(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    bounds: TokenStream,
+) -> TokenStream {
+    // without concrete types there is nothing meaningful to check
+    if generics.type_params().next().is_some() || generics.const_params().next().is_some() {
+        return quote!();
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        const _: () = {
+            fn assert<T: ?::core::marker::Sized + #bounds>() {}
+            fn check #impl_generics () #where_clause {
+                assert::<#ident #ty_generics>();
+            }
+        };
+    }
+}
+
+/// check that a field is an error where the field is declared, instead of
+/// leaving it to a confusing error inside the generated code
+fn generate_error_assert(generics: &syn::Generics, ty: &syn::Type, what: &str) -> TokenStream {
+    if generics.type_params().next().is_some() || generics.const_params().next().is_some() {
+        return quote!();
+    }
+    // boxed trait objects only reach their error through deref
+    let mut ty = ty;
+    if let syn::Type::Path(syn::TypePath { ref path, .. }) = ty {
+        match path.segments.last() {
+            Some(syn::PathSegment {
+                ident,
+                arguments: syn::PathArguments::AngleBracketed(args),
+            }) if ident == "Box" => match args.args.first() {
+                Some(syn::GenericArgument::Type(inner)) => ty = inner,
+                _ => return quote!(),
+            },
+            _ => {}
+        }
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    // the trait name carries the message, since #[diagnostic] attributes
+    // can't be found under no_implicit_prelude
+    let check = quote::format_ident!("{what}FieldMustBeAnError");
+
+    quote! {
+        const _: () = {
+            trait #check {}
+            impl<T: ?::core::marker::Sized + ::core::error::Error> #check for T {}
+            fn assert<T: ?::core::marker::Sized + #check>() {}
+            fn check #impl_generics () #where_clause {
+                assert::<#ty>();
+            }
+        };
+    }
+}
+
+/// the name of an identifier as it should be shown, without any `r#`
+fn unraw(ident: &syn::Ident) -> String {
+    syn::ext::IdentExt::unraw(ident).to_string()
+}
+
+fn variant_msg(v: &Variant, variant_name: &VariantName) -> Option<Msg> {
+    let name = unraw(&v.ident);
+    let msg = match v.msg {
+        Some(Msg::Str(ref msg)) => Some(Msg::Str(msg.clone())),
+        Some(Msg::Macro(ref msg)) => Some(Msg::Macro(msg.clone())),
+        Some(Msg::Const(ref msg)) => Some(Msg::Const(msg.clone())),
+        None => None,
+    };
+    match (variant_name, msg) {
+        (VariantName::Always, Some(msg)) => Some(msg.prefix(&format!("{name}: "))),
+        (VariantName::Never, None) => None,
+        (_, Some(msg)) => Some(msg),
+        (_, None) => Some(Msg::Str(name)),
+    }
+}
+
+enum MsgPart {
+    Lit(String),
+    Field(usize),
+}
+
+/// split a message on placeholders naming its variant's fields, leaving
+/// any other braces alone. doubled braces stand for one, like with format!
+fn parse_msg(msg: &str, fields: &[Field]) -> Vec<MsgPart> {
+    let lookup = |name: &str| {
+        let named = fields
+            .iter()
+            .enumerate()
+            .position(|(fnum, f)| match f.ident {
+                Some(ref fnm) => fnm == name,
+                None => name.parse() == Ok(fnum),
+            });
+        named.or_else(|| fields.iter().position(|f| f.source && name == "source"))
+    };
+    let mut parts = vec![];
+    let mut lit = String::new();
+    let mut rest = msg;
+    while let Some(start) = rest.find(['{', '}']) {
+        let (before, after) = rest.split_at(start);
+        lit.push_str(before);
+        // `{{` and `}}` are one brace each, and so is a lone `}`
+        let (brace, next) = after.split_at(1);
+        if brace == "}" || next.starts_with('{') {
+            lit.push_str(brace);
+            rest = next.strip_prefix(brace).unwrap_or(next);
+            continue;
+        }
+        let field = after[1..]
+            .find('}')
+            .and_then(|end| Some((end, lookup(&after[1..end + 1])?)));
+        match field {
+            Some((end, fnum)) => {
+                if !lit.is_empty() {
+                    parts.push(MsgPart::Lit(std::mem::take(&mut lit)));
+                }
+                parts.push(MsgPart::Field(fnum));
+                rest = &after[end + 2..];
+            }
+            None => {
+                lit.push('{');
+                rest = &after[1..];
+            }
+        }
+    }
+    lit.push_str(rest);
+    if !lit.is_empty() {
+        parts.push(MsgPart::Lit(lit));
+    }
+    parts
+}
+
+/// a display arm putting each field on its own line, for variants showing
+/// their fields the usual way
+fn generate_pretty_arm(v: &Variant, variant_name: &VariantName) -> Option<TokenStream> {
+    if v.transparent || v.template.is_some() || !v.fields.iter().any(Field::listed) {
+        return None;
+    }
+    let msg = variant_msg(v, variant_name);
+    if let Some(Msg::Str(ref msg)) = msg {
+        if parse_msg(msg, &v.fields)
+            .iter()
+            .any(|p| matches!(p, MsgPart::Field(_)))
+        {
+            return None;
+        }
+    }
+    let (pat, get) = variant_pattern(&quote!(Self), v, Field::shown);
+    let mut fmt = vec![];
+    let mut args = vec![];
+    if let (Some(fnum), Some(_)) = (v.prefix_field, &msg) {
+        fmt.push("{}: ".to_string());
+        args.push(field_value(&v.fields[fnum], &get[fnum]));
+    }
+    let indent = match msg.map(Msg::unescape) {
+        Some(Msg::Str(msg)) => {
+            fmt.push(msg.replace('{', "{{").replace('}', "}}"));
+            "\n  "
+        }
+        Some(msg) => {
+            fmt.push("{}".to_string());
+            args.push(quote!(#msg));
+            "\n  "
+        }
+        None => "\n",
+    };
+    for (field, fid) in v.fields.iter().zip(&get).filter(|(f, _)| f.listed()) {
+        if !fmt.is_empty() {
+            fmt.push(indent.to_string());
+        }
+        if let Some(ref fnm) = field.ident {
+            fmt.push(format!("{fnm}: "));
+        }
+        fmt.push(if field.quote { "\"{}\"" } else { "{}" }.to_string());
+        args.push(field_value(field, fid));
+    }
+    if let Some(ref terminator) = v.terminator {
+        fmt.push(terminator.replace('{', "{{").replace('}', "}}"));
+    }
+
+    Some(write_arm(std::slice::from_ref(&pat), &fmt, &args, true))
+}
+
+/// enums with at least this many variants share display arms between
+/// variants shaped the same way
+const SHARED_ARM_VARIANTS: usize = 64;
+
+/// a display arm for any of the patterns, writing the pieces of a format
+/// string into `f` as a single literal, or as plain text if nothing needs
+/// formatting. `f` is a Formatter unless generating write_to, in which case
+/// transparent variants can't hand it on to the inner error
+fn write_arm(
+    pats: &[TokenStream],
+    fmt: &[String],
+    args: &[TokenStream],
+    formatter: bool,
+) -> TokenStream {
+    if let ([], [inner]) = (fmt, args) {
+        return match formatter {
+            true => quote!(#(#pats)|* => ::core::fmt::Display::fmt(#inner, f)),
+            false => quote!(#(#pats)|* => ::core::write!(f, "{}", #inner)),
+        };
+    }
+    let fmt = fmt.concat();
+    if args.is_empty() {
+        let text = fmt.replace("{{", "{").replace("}}", "}");
+        return quote!(#(#pats)|* => f.write_str(#text));
+    }
+    quote!(#(#pats)|* => ::core::write!(f, #fmt #(, #args)*))
+}
+
+fn field_value(field: &Field, fid: &syn::Ident) -> TokenStream {
+    // a field's type missing a trait gets pointed out at the field
+    let span = Span::call_site().located_at(field.span);
+    match field.render {
+        Render::Display => quote!(#fid),
+        Render::Debug => quote_spanned!(span=> __FoxDebug(#fid)),
+        Render::Path => quote_spanned!(span=> #fid.display()),
+        Render::List(Some(ref bullet)) => {
+            quote_spanned!(span=> __FoxList(#fid, ::core::option::Option::Some(#bullet)))
+        }
+        Render::List(None) => {
+            quote_spanned!(span=> __FoxList(#fid, ::core::option::Option::None))
+        }
+        Render::Duration => quote_spanned!(span=> __FoxDuration(#fid)),
+        Render::Bool(ref yes, ref no) => quote_spanned!(span=> if *#fid { #yes } else { #no }),
+        Render::Via(ref adapter) => {
+            quote_spanned!(span=> <#adapter as ::core::convert::From<_>>::from(#fid))
+        }
+    }
+}
+
+fn message_const(variant: &syn::Ident) -> syn::Ident {
+    syn::Ident::new(&format!("__FOX_MESSAGE_{}", unraw(variant)), variant.span())
+}
+
+fn generate_message(path: syn::Path) -> syn::Result<TokenStream> {
+    let mut ty = path.clone();
+    let variant = match ty.segments.pop() {
+        Some(variant) if !ty.segments.is_empty() => variant.into_value(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                path,
+                "expected a path to a variant, like Error::NotFound",
+            ))
+        }
+    };
+    ty.segments.pop_punct();
+    let name = message_const(&variant.ident);
+    // inherent constants win over trait ones, so this one is only reached
+    // when the derive didn't generate a constant for the variant
+    let missing = format!(
+        "{} has no message constant, it needs to be a unit variant of an enum with #[err(message_consts)]",
+        quote!(#path).to_string().replace(' ', ""),
+    );
+
+    Ok(quote!({
+        trait __FoxNoMessage {
+            const #name: &'static str = ::core::panic!(#missing);
+        }
+        impl<T: ?::core::marker::Sized> __FoxNoMessage for T {}
+        <#ty>::#name
+    }))
+}
+
+fn is_str_ref(ty: &syn::Type) -> bool {
+    let syn::Type::Reference(syn::TypeReference { ref elem, .. }) = ty else {
+        return false;
+    };
+    matches!(**elem, syn::Type::Path(ref p) if p.path.is_ident("str"))
+}
+
+/// a borrowed trait object that isn't `'static` itself, like `&'a dyn Error`
+fn is_borrowed_dyn(ty: &syn::Type) -> bool {
+    let syn::Type::Reference(syn::TypeReference { ref elem, .. }) = ty else {
+        return false;
+    };
+    let mut elem = &**elem;
+    while let syn::Type::Paren(syn::TypeParen {
+        elem: ref inner, ..
+    })
+    | syn::Type::Group(syn::TypeGroup {
+        elem: ref inner, ..
+    }) = elem
+    {
+        elem = inner;
+    }
+    let syn::Type::TraitObject(ref obj) = elem else {
+        return false;
+    };
+    !obj.bounds
+        .iter()
+        .any(|b| matches!(b, syn::TypeParamBound::Lifetime(lt) if lt.ident == "static"))
+}
+
+fn generate_leak(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    variants: &[Variant],
+    vis: &syn::Visibility,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let params = generics.params.iter().map(|p| match p {
+        syn::GenericParam::Lifetime(_) => quote!('static),
+        syn::GenericParam::Type(syn::TypeParam { ident, .. })
+        | syn::GenericParam::Const(syn::ConstParam { ident, .. }) => quote!(#ident),
+    });
+    let arms = variants.iter().map(|v| {
+        let (from, get) = variant_pattern(&quote!(Self), v, |_| true);
+        let (to, _) = variant_pattern(ident, v, |_| true);
+        let leaked = v
+            .fields
+            .iter()
+            .zip(&get)
+            .filter(|(f, _)| v.as_static && is_str_ref(&f.ty))
+            .map(|(_, fid)| fid);
+        quote! {
+            #from => {
+                #(let #leaked: &'static str = ::std::boxed::Box::leak(
+                    <::std::string::String as ::core::convert::From<&str>>::from(#leaked)
+                        .into_boxed_str()
+                );)*
+                #to
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// turn this error into one that lives forever, by leaking the
+            /// strings it borrows
+            ///
+            /// **this leaks memory every time it is called.** it is up to
+            /// you to only do so for errors that are kept around for good,
+            /// and never in a loop.
+            #vis fn leak(self) -> #ident<#(#params),*> {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    }
+}
+
+/// the literal pieces around each displayed field, or `None` if the
+/// variant's message can't be parsed back
+fn display_pieces(v: &Variant, variant_name: &VariantName) -> Option<Vec<TokenStream>> {
+    let reversible = |f: &Field| {
+        f.implicit.is_some() || (f.shown() && matches!(f.render, Render::Display | Render::Path))
+    };
+    if v.transparent
+        || v.template.is_some()
+        || v.prefix_field.is_some()
+        || !v.fields.iter().all(reversible)
+    {
+        return None;
+    }
+    let msg = variant_msg(v, variant_name);
+    if let Some(Msg::Str(ref msg)) = msg {
+        if parse_msg(msg, &v.fields)
+            .iter()
+            .any(|p| matches!(p, MsgPart::Field(_)))
+        {
+            return None;
+        }
+    }
+    let shown: Vec<_> = v.fields.iter().filter(|f| f.shown()).collect();
+    let mut pieces = vec![];
+    let mut lit = String::new();
+    for (fnum, field) in shown.iter().enumerate() {
+        if fnum > 0 {
+            lit.push_str(", ");
+        } else if msg.is_some() {
+            lit.push_str(": ");
+        }
+        if let Some(ref fnm) = field.ident {
+            lit.push_str(&format!("{fnm}: "));
+        }
+        if field.quote {
+            lit.push('"');
+        }
+        pieces.push(std::mem::take(&mut lit));
+        if field.quote {
+            lit.push('"');
+        }
+    }
+    if msg.is_some() && !matches!(v.style, Style::Unit) && v.fields.is_empty() {
+        lit.push(':');
+    }
+    lit.push_str(v.terminator.as_deref().unwrap_or_default());
+    pieces.push(lit);
+    let mut pieces = pieces.into_iter();
+    let first = pieces.next().unwrap_or_default();
+    let first = match msg {
+        Some(msg) => msg.unescape().suffix(&first),
+        None => Msg::Str(first),
+    };
+    Some(
+        std::iter::once(quote!(#first))
+            .chain(pieces.map(|p| quote!(#p)))
+            .collect(),
+    )
+}
+
+fn generate_from_str(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    variants: &[Variant],
+    variant_name: &VariantName,
+    err: &TokenStream,
+) -> TokenStream {
+    let mut generics = generics.clone();
+    let attempts: Vec<_> = variants
+        .iter()
+        .filter_map(|v| Some((v, display_pieces(v, variant_name)?)))
+        .map(|(v, pieces)| {
+            let name = &v.ident;
+            let mut shown = 0..;
+            let vals: Vec<_> = v
+                .fields
+                .iter()
+                .map(|f| match f.implicit {
+                    Some(ref init) => init.clone(),
+                    None => {
+                        let ty = &f.ty;
+                        generics
+                            .make_where_clause()
+                            .predicates
+                            .push(syn::parse_quote!(#ty: ::core::str::FromStr));
+                        let val = syn::Ident::new(
+                            &format!("val_{}", shown.next().unwrap()),
+                            Span::mixed_site(),
+                        );
+                        quote!(#val.parse().ok()?)
+                    }
+                })
+                .collect();
+            let build = match v.style {
+                Style::Named => {
+                    let names = v.fields.iter().map(|f| &f.ident);
+                    quote!(Self::#name { #(#names: #vals),* })
+                }
+                Style::Unnamed => quote!(Self::#name(#(#vals),*)),
+                Style::Unit => quote!(Self::#name),
+            };
+            let (first, rest) = pieces.split_first().unwrap();
+            let Some((last, between)) = rest.split_last() else {
+                return quote! {
+                    if s == #first {
+                        return ::core::result::Result::Ok(#build);
+                    }
+                };
+            };
+            let split = between.iter().enumerate().map(|(fnum, piece)| {
+                let val = syn::Ident::new(&format!("val_{fnum}"), Span::mixed_site());
+                quote!(let (#val, rest) = rest.split_once(#piece)?;)
+            });
+            let last_val = syn::Ident::new(&format!("val_{}", between.len()), Span::mixed_site());
+            quote! {
+                let parsed = (|| {
+                    let rest = s.strip_prefix(#first)?.strip_suffix(#last)?;
+                    #(#split)*
+                    let #last_val = rest;
+                    ::core::option::Option::Some(#build)
+                })();
+                if let ::core::option::Option::Some(parsed) = parsed {
+                    return ::core::result::Result::Ok(parsed);
+                }
+            }
+        })
+        .collect();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::core::str::FromStr for #ident #ty_generics #where_clause {
+            type Err = #err;
+
+            #[allow(unused_variables)]
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                #(#attempts)*
+                ::core::result::Result::Err(#err)
+            }
+        }
+    }
+}
+
+/// a pattern matching the variant, with `ty` being the enum's path or `Self`
+fn variant_pattern(
+    ty: &impl quote::ToTokens,
+    v: &Variant,
+    used: impl Fn(&Field) -> bool,
+) -> (TokenStream, Vec<syn::Ident>) {
+    // mixed_site keeps the bindings from clashing with anything a
+    // declarative macro around the enum brings into scope, while errors
+    // about them still point at the field
+    let get: Vec<_> = v
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(fnum, f)| {
+            let span = Span::mixed_site().located_at(f.span);
+            syn::Ident::new(format!("arg_{fnum}").as_ref(), span)
+        })
+        .collect();
+    let bind = v.fields.iter().zip(&get).map(
+        |(field, fid)| {
+            if used(field) {
+                quote!(#fid)
+            } else {
+                quote!(_)
+            }
+        },
+    );
+    let name = &v.ident;
+    let set = match v.style {
+        Style::Named => {
+            let ids = v.fields.iter().map(|f| &f.ident);
+            quote!({#(#ids: #bind),*})
+        }
+        Style::Unnamed => quote!((#(#bind),*)),
+        Style::Unit => quote!(),
+    };
+
+    (quote!(#ty::#name #set), get)
+}
+
+fn field_pairs(v: &Variant) -> (TokenStream, Vec<TokenStream>) {
+    let used = |f: &Field| v.transparent || f.shown();
+    let (pat, get) = variant_pattern(&quote!(Self), v, used);
+    let pairs = v
+        .fields
+        .iter()
+        .zip(&get)
+        .enumerate()
+        .filter(|(_, (f, _))| used(f))
+        .map(|(fnum, (field, fid))| {
+            let key = match field.ident {
+                Some(ref fnm) => fnm.to_string(),
+                None => fnum.to_string(),
+            };
+            let val = field_value(field, fid);
+            quote!((#key, ::std::format!("{}", #val)))
+        })
+        .collect();
+    (pat, pairs)
+}
+
+/// a Serialize implementation giving the variant's name, its message as
+/// displayed, and the fields shown in it
+fn generate_serialize(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    variants: &[Variant],
+) -> TokenStream {
+    let serialized = |v: &Variant, f: &Field| !v.transparent && !f.source && f.shown();
+    let mut generics = generics.clone();
+    if generics.type_params().next().is_some() {
+        let where_clause = generics.make_where_clause();
+        for v in variants {
+            for f in v.fields.iter().filter(|f| serialized(v, f)) {
+                let ty = &f.ty;
+                where_clause
+                    .predicates
+                    .push(syn::parse_quote!(#ty: ::serde::Serialize));
+            }
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let names: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    let strs = names.iter().map(|n| unraw(n));
+    let arms = variants.iter().map(|v| {
+        let (pat, get) = variant_pattern(ident, v, |f| serialized(v, f));
+        let (keys, vals): (Vec<_>, Vec<_>) = v
+            .fields
+            .iter()
+            .zip(&get)
+            .enumerate()
+            .filter(|(_, (f, _))| serialized(v, f))
+            .map(|(fnum, (field, fid))| match field.ident {
+                Some(ref fnm) => (unraw(fnm), fid),
+                None => (fnum.to_string(), fid),
+            })
+            .unzip();
+        let len = keys.len();
+        quote! {
+            #pat => {
+                let mut map = ::serde::Serializer::serialize_map(
+                    serializer,
+                    ::core::option::Option::Some(#len),
+                )?;
+                #(::serde::ser::SerializeMap::serialize_entry(&mut map, #keys, #vals)?;)*
+                ::serde::ser::SerializeMap::end(map)
+            }
+        }
+    });
+    let name = unraw(ident);
+    let body = match names[..] {
+        [] => quote!(match *self {}),
+        _ => quote! {
+            struct __FoxMessage<'a, T: ?::core::marker::Sized>(&'a T);
+            impl<T: ?::core::marker::Sized + ::core::fmt::Display> ::serde::Serialize
+                for __FoxMessage<'_, T>
+            {
+                fn serialize<__S: ::serde::Serializer>(
+                    &self,
+                    serializer: __S,
+                ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                    ::serde::Serializer::collect_str(serializer, self.0)
+                }
+            }
+
+            struct __FoxFields<'a, T: ?::core::marker::Sized>(&'a T);
+            impl #impl_generics ::serde::Serialize
+                for __FoxFields<'_, #ident #ty_generics> #where_clause
+            {
+                fn serialize<__S: ::serde::Serializer>(
+                    &self,
+                    serializer: __S,
+                ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                    match self.0 {
+                        #(#arms)*
+                    }
+                }
+            }
+
+            let variant: &'static str = match self {
+                #(Self::#names { .. } => #strs,)*
+            };
+            let mut state = ::serde::Serializer::serialize_struct(serializer, #name, 3)?;
+            ::serde::ser::SerializeStruct::serialize_field(&mut state, "variant", variant)?;
+            ::serde::ser::SerializeStruct::serialize_field(
+                &mut state,
+                "message",
+                &__FoxMessage(self),
+            )?;
+            ::serde::ser::SerializeStruct::serialize_field(
+                &mut state,
+                "fields",
+                &__FoxFields(self),
+            )?;
+            ::serde::ser::SerializeStruct::end(state)
+        },
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::serde::Serialize for #ident #ty_generics #where_clause {
+            fn serialize<__S: ::serde::Serializer>(
+                &self,
+                serializer: __S,
+            ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                #body
+            }
+        }
+    }
+}
+
+/// the generic parameters `tokens` mention, keeping only the bounds and
+/// where clauses that don't involve any of the others
+fn used_generics(generics: &syn::Generics, tokens: TokenStream) -> syn::Generics {
+    fn names(tokens: TokenStream, found: &mut Vec<String>) {
+        let mut lifetime = false;
+        for t in tokens {
+            match t {
+                proc_macro2::TokenTree::Group(ref g) => names(g.stream(), found),
+                proc_macro2::TokenTree::Ident(ref i) if lifetime => found.push(format!("'{i}")),
+                proc_macro2::TokenTree::Ident(ref i) => found.push(i.to_string()),
+                proc_macro2::TokenTree::Punct(_) | proc_macro2::TokenTree::Literal(_) => {}
+            }
+            lifetime = matches!(t, proc_macro2::TokenTree::Punct(ref p) if p.as_char() == '\'');
+        }
+    }
+    let name = |p: &syn::GenericParam| match p {
+        syn::GenericParam::Lifetime(l) => l.lifetime.to_string(),
+        syn::GenericParam::Type(t) => t.ident.to_string(),
+        syn::GenericParam::Const(c) => c.ident.to_string(),
+    };
+    let mut found = vec![];
+    names(tokens, &mut found);
+    let all: Vec<_> = generics.params.iter().map(name).collect();
+    let allowed = |tokens: TokenStream| {
+        let mut mentioned = vec![];
+        names(tokens, &mut mentioned);
+        mentioned
+            .iter()
+            .all(|n| !all.contains(n) || found.contains(n))
+    };
+
+    let mut used = generics.clone();
+    used.params = used
+        .params
+        .into_iter()
+        .filter(|p| found.contains(&name(p)))
+        .map(|mut p| {
+            match p {
+                syn::GenericParam::Lifetime(ref mut l) => {
+                    l.bounds = l
+                        .bounds
+                        .iter()
+                        .filter(|b| allowed(quote!(#b)))
+                        .cloned()
+                        .collect();
+                }
+                syn::GenericParam::Type(ref mut t) => {
+                    t.bounds = t
+                        .bounds
+                        .iter()
+                        .filter(|b| allowed(quote!(#b)))
+                        .cloned()
+                        .collect();
+                }
+                syn::GenericParam::Const(_) => {}
+            }
+            p
+        })
+        .collect();
+    if let Some(ref mut where_clause) = used.where_clause {
+        where_clause.predicates = where_clause
+            .predicates
+            .iter()
+            .filter(|pred| allowed(quote!(#pred)))
+            .cloned()
+            .collect();
+    }
+    used
+}
+
+fn generate_subset(
+    subset: &Subset,
+    vis: &TokenStream,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    variants: &[Variant],
+    args: &[AttrArg],
+    krate: &TokenStream,
+) -> TokenStream {
+    let name = &subset.ident;
+    let mut defs = vec![];
+    let mut arms = vec![];
+    for vname in &subset.variants {
+        let Some(v) = variants.iter().find(|v| v.ident == *vname) else {
+            let msg = format!("no variant named {vname} in {ident}");
+            return syn::Error::new(vname.span(), msg).to_compile_error();
+        };
+        defs.extend(&v.def);
+        let (from, _) = variant_pattern(name, v, |_| true);
+        let (to, _) = variant_pattern(&quote!(Self), v, |_| true);
+        arms.push(quote!(#from => #to));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // parameters none of the copied variants use would be rejected as unused
+    let fields = defs.iter().map(|d| &d.fields);
+    let sub_generics = used_generics(generics, quote!(#(#fields)*));
+    let (_, sub_ty_generics, sub_where_clause) = sub_generics.split_for_impl();
+
+    let args = (!args.is_empty()).then(|| quote!(#[err(#(#args),*)]));
+
+    quote! {
+        #[derive(::core::fmt::Debug, #krate::FoxError)]
+        #args
+        #vis enum #name #sub_generics #sub_where_clause {
+            #(#defs,)*
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::From<#name #sub_ty_generics> for #ident #ty_generics #where_clause {
+            fn from(err: #name #sub_ty_generics) -> Self {
+                match err {
+                    #(#arms,)*
+                }
+            }
+        }
+    }
+}
+
+fn generate_opaque(
+    (vis, name): &(syn::Visibility, syn::Ident),
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    has_exit_code: bool,
+    has_codes: bool,
+    kind: Option<&syn::Ident>,
+    method_vis: &MethodVis,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let kind = kind.map(|kind| {
+        let vis = method_vis.get("kind");
+        quote! {
+            /// the kind of this error
+            #vis fn kind(&self) -> #kind {
+                self.0.kind()
+            }
+        }
+    });
+    let exit_code = has_exit_code.then(|| {
+        let vis = method_vis.get("exit_code");
+        quote! {
+            /// the exit code to use when exiting due to this error
+            #vis fn exit_code(&self) -> i32 {
+                self.0.exit_code()
+            }
+        }
+    });
+    let error_code = has_codes.then(|| {
+        let vis = method_vis.get("error_code");
+        quote! {
+            /// the code identifying this error
+            #vis fn error_code(&self) -> i32 {
+                self.0.error_code()
+            }
+        }
+    });
+
+    quote! {
+        /// an error whose details are kept private
+        #vis struct #name #generics (#ident #ty_generics) #where_clause;
+
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::core::error::Error for #name #ty_generics #where_clause {
+            fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                ::core::error::Error::source(&self.0)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #name #ty_generics #where_clause {
+            fn from(err: #ident #ty_generics) -> Self {
+                Self(err)
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #exit_code
+            #error_code
+            #kind
+        }
+    }
+}
+
+fn generate_into(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    variants: &[Variant],
+    target: &syn::Expr,
+    with: Option<&syn::Expr>,
+) -> TokenStream {
+    let mut arms = vec![];
+    let mut missing = vec![];
+    for v in variants {
+        let (pat, get) = variant_pattern(ident, v, |_| true);
+        let name = &v.ident;
+        match (&v.into, &get[..]) {
+            (Some(path), []) => arms.push(quote!(#pat => #path)),
+            (Some(path), [inner]) => arms.push(quote!(#pat => #path(#inner))),
+            (Some(path), _) => {
+                let msg = "into requires a variant with at most one field";
+                return syn::Error::new_spanned(path, msg).to_compile_error();
+            }
+            (None, _) if with.is_some() => {}
+            (None, []) => arms.push(quote!(#pat => #target::#name)),
+            (None, _) => missing.push(name.to_string()),
+        }
+    }
+    // everything else is left to the function
+    if let Some(with) = with {
+        arms.push(quote!(err => #with(err)));
+    }
+    if !missing.is_empty() {
+        let msg = format!(
+            "no conversion into {} for {}, add #[err(into = ...)] to them",
+            quote!(#target),
+            missing.join(", "),
+        );
+        return syn::Error::new_spanned(target, msg).to_compile_error();
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #target #where_clause {
+            fn from(err: #ident #ty_generics) -> Self {
+                #[allow(unreachable_patterns)]
+                match err {
+                    #(#arms,)*
+                }
+            }
+        }
+    }
+}
+
+fn generate_boxed(
+    vis: &TokenStream,
+    name: &syn::Ident,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    variants: &[Variant],
+    method_vis: &MethodVis,
+    track_caller: bool,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let into_inner_vis = method_vis.get("into_inner");
+    let froms = variants
+        .iter()
+        .filter(|v| v.from)
+        .filter_map(|v| Some((v, v.fields.iter().find(|f| f.implicit.is_none())?)))
+        .map(|(v, field)| {
+            let ty = &field.ty;
+            // the location has to make it through the enum's From too
+            let track_caller = (track_caller || v.fields.len() > 1).then(|| quote!(#[track_caller]));
+            quote! {
+                #[automatically_derived]
+                impl #impl_generics ::core::convert::From<#ty> for #name #ty_generics #where_clause {
+                    #track_caller
+                    fn from(err: #ty) -> Self {
+                        Self(::std::boxed::Box::new(#ident::from(err)))
+                    }
+                }
+            }
+        });
+
+    quote! {
+        /// a boxed error, to keep results small
+        #vis struct #name #generics (::std::boxed::Box<#ident #ty_generics>) #where_clause;
+
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::core::error::Error for #name #ty_generics #where_clause {
+            fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                ::core::error::Error::source(&*self.0)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #name #ty_generics #where_clause {
+            fn from(err: #ident #ty_generics) -> Self {
+                Self(::std::boxed::Box::new(err))
+            }
+        }
+
+        #(#froms)*
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// unbox the error
+            #into_inner_vis fn into_inner(self) -> #ident #ty_generics {
+                *self.0
+            }
+        }
+    }
+}
+
+fn generate_kind_enum(
+    vis: &TokenStream,
+    kind: &syn::Ident,
+    variants: &[Variant],
+    codes: Option<&Vec<&syn::Expr>>,
+) -> TokenStream {
+    let repr = codes.map(|_| quote!(#[repr(i32)]));
+    let kinds = variants.iter().map(|v| {
+        let name = &v.ident;
+        match (codes, &v.code) {
+            (Some(_), Some(code)) => quote!(#name = #code),
+            _ => quote!(#name),
+        }
+    });
+
+    quote! {
+        /// the kind of an error, without any of its fields
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        #repr
+        #vis enum #kind {
+            #(#kinds,)*
+        }
+    }
+}
+
+fn generate_result_alias(
+    vis: &TokenStream,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    alias: &syn::Ident,
+) -> TokenStream {
+    // bounds aren't enforced on type aliases, so only names and defaults are
+    // carried over. the ok type goes before any defaulted parameters
+    let lifetimes = generics.lifetimes().map(|l| &l.lifetime);
+    let params = generics.params.iter().filter_map(|p| match p {
+        syn::GenericParam::Lifetime(_) => None,
+        syn::GenericParam::Type(t) => {
+            let (name, default) = (&t.ident, t.default.iter());
+            Some(quote!(#name #(= #default)*))
+        }
+        syn::GenericParam::Const(c) => {
+            let (name, ty, default) = (&c.ident, &c.ty, c.default.iter());
+            Some(quote!(const #name: #ty #(= #default)*))
+        }
+    });
+    // avoid clashing with the enum's own type parameters
+    let taken = |name: &str| generics.type_params().any(|t| t.ident == name);
+    let ok = (0..)
+        .map(|n| match n {
+            0 => "T".to_string(),
+            n => format!("T{n}"),
+        })
+        .find(|name| !taken(name))
+        .map(|name| syn::Ident::new(&name, Span::call_site()))
+        .unwrap();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let err = quote!(#ident #ty_generics);
+    let err_param = generics.params.is_empty().then(|| quote!(, E = #err));
+    let err_ty = match err_param {
+        Some(_) => quote!(E),
+        None => err.clone(),
+    };
+
+    quote! {
+        #[allow(dead_code)]
+        #vis type #alias<#(#lifetimes,)* #ok #(, #params)* #err_param> =
+            ::core::result::Result<#ok, #err_ty>;
+    }
+}
+
+/// placeholder impls emitted next to errors, so that code using the enum as
+/// an error doesn't pile more errors on top
+fn generate_fallback(input: &DeriveInput, error: bool) -> TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let error = error.then(|| {
+        // a derived Debug only covers some parameters, so leave out the
+        // rest rather than pointing out that they aren't Debug
+        let mut generics = input.generics.clone();
+        if !generics.params.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(syn::parse_quote!(Self: ::core::fmt::Debug));
+        }
+        let where_clause = &generics.where_clause;
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::error::Error for #ident #ty_generics #where_clause {}
+        }
+    });
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, _: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        #error
+    }
+}
+
+/// the value of an integer literal, which may be negated
+fn int_value(expr: &syn::Expr) -> Option<i64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(i),
+            ..
+        }) => i.base10_parse().ok(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => int_value(expr)?.checked_neg(),
+        _ => None,
+    }
+}
+
+/// catch what the attributes alone can't before generating anything
+fn validate(parsed: &ParsedErrors) -> syn::Result<()> {
+    let mut errors = vec![];
+    let mut error = |span: &dyn quote::ToTokens, msg: &str| {
+        errors.push(syn::Error::new_spanned(span, msg));
+    };
+    let variants = &parsed.variants;
+    for v in variants {
+        let name = &v.ident;
+        let explicit = v.fields.iter().filter(|f| f.implicit.is_none()).count();
+        if v.transparent && v.fields.len() != 1 {
+            error(name, "transparent variants must have exactly one field");
+        }
+        if v.from && !matches!(v.style, Style::Unnamed) {
+            error(
+                name,
+                "automatically deriving From is only supported for unnamed fields",
+            );
+        } else if v.from && explicit != 1 {
+            error(
+                name,
+                "automatically deriving From is only supported with a single field",
+            );
+        }
+        if parsed.error_trait.is_some() && (v.transparent || v.fields.iter().any(|f| f.source)) {
+            error(
+                name,
+                "sources are returned as core::error::Error, which error_trait replaces",
+            );
+        }
+        for field in v
+            .fields
+            .iter()
+            .filter(|f| f.source && is_borrowed_dyn(&f.ty))
+        {
+            error(
+                &field.ty,
+                "sources are returned as `dyn Error + 'static`, so borrowed ones need to look like `&'a (dyn Error + 'static)`",
+            );
+        }
+        // a message mentioning fields replaces the list of them, so any
+        // it doesn't mention would never be shown
+        let shows_fields = v
+            .template
+            .as_ref()
+            .is_some_and(|t| t.iter().any(|p| matches!(p, TemplatePart::Fields)));
+        if let (Some(Msg::Str(ref msg)), false) = (&v.msg, shows_fields || v.transparent) {
+            let parts = parse_msg(msg, &v.fields);
+            let referenced: Vec<_> = parts
+                .iter()
+                .filter_map(|p| match p {
+                    MsgPart::Field(fnum) => Some(*fnum),
+                    MsgPart::Lit(_) => None,
+                })
+                .collect();
+            let unused: Vec<_> = v
+                .fields
+                .iter()
+                .enumerate()
+                .filter(|(fnum, f)| f.listed() && !referenced.contains(fnum))
+                .map(|(fnum, f)| match f.ident {
+                    Some(ref fnm) => format!("`{fnm}`"),
+                    None => format!("`{fnum}`"),
+                })
+                .collect();
+            if !referenced.is_empty() && !unused.is_empty() {
+                let them = if unused.len() == 1 { "it" } else { "them" };
+                let msg = format!(
+                    "the message of {name} leaves out {}, mention {them} with {{...}} or mark {them} #[err(skip)]",
+                    unused.join(", "),
+                );
+                error(name, &msg);
+            }
+        }
+        if v.prefix_field.is_some() && variant_msg(v, &parsed.variant_name).is_none() {
+            error(
+                name,
+                "message_prefix_field requires the variant to have a message",
+            );
+        }
+        let plain = matches!(v.style, Style::Unit) && v.template.is_none() && !v.transparent;
+        if parsed.static_table && !plain {
+            error(
+                name,
+                "static_table requires every variant to be a plain unit variant",
+            );
+        }
+        if let (true, Some(discriminant)) = (parsed.static_table, &v.discriminant) {
+            error(
+                discriminant,
+                "static_table looks messages up by discriminant, so variants can't set their own",
+            );
+        }
+    }
+    if parsed.generics.lifetimes().next().is_none() {
+        if let Some(v) = variants.iter().find(|v| v.as_static) {
+            error(
+                &v.ident,
+                "as_static requires the enum to have a lifetime parameter",
+            );
+        }
+    }
+    if parsed.deny_duplicate_messages {
+        // messages from macros can't be known until they are expanded
+        fn literal(v: &Variant) -> Option<&String> {
+            match v.msg {
+                Some(Msg::Str(ref msg)) if !v.transparent => Some(msg),
+                _ => None,
+            }
+        }
+        for (i, v) in variants.iter().enumerate() {
+            let Some(msg) = literal(v) else {
+                continue;
+            };
+            if let Some(prev) = variants[..i].iter().find(|p| literal(p) == Some(msg)) {
+                let msg = format!("{} has the same message as {}", v.ident, prev.ident);
+                error(&v.ident, &msg);
+            }
+        }
+    }
+    // two From impls for the same type would only show up as a coherence
+    // error on the derive, so name every variant involved instead
+    let mut froms: Vec<(String, Vec<(&Variant, &syn::Type)>)> = vec![];
+    for v in variants.iter().filter(|v| v.from) {
+        let Some(field) = v.fields.iter().find(|f| f.implicit.is_none()) else {
+            continue;
+        };
+        let ty = &field.ty;
+        let key = quote!(#ty).to_string();
+        match froms.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, vs)) => vs.push((v, ty)),
+            None => froms.push((key, vec![(v, ty)])),
+        }
+    }
+    for (_, vs) in froms.iter().filter(|(_, vs)| vs.len() > 1) {
+        let (last, rest) = vs.split_last().expect("more than one");
+        let names: Vec<_> = rest.iter().map(|(v, _)| format!("`{}`", v.ident)).collect();
+        let msg = format!(
+            "{} and `{}` are all converted from this type, only one of them can be marked from",
+            names.join(", "),
+            last.0.ident,
+        );
+        for (_, ty) in vs {
+            error(ty, &msg);
+        }
+    }
+    if variants.iter().any(|v| v.key.is_some()) {
+        if let Some(v) = variants.iter().find(|v| v.key.is_none()) {
+            error(
+                &v.ident,
+                "either every variant or none of them must have a key",
+            );
+        }
+    }
+    if let Some(v) = variants.iter().filter(|v| v.other).nth(1) {
+        error(&v.ident, "only one variant can be marked other");
+    }
+    // the kind enum uses the codes as its discriminants, which would
+    // otherwise clash with a less helpful error. only literals can be
+    // compared here
+    if parsed.error_kind {
+        let codes: Vec<_> = variants
+            .iter()
+            .filter_map(|v| Some((v, v.code.as_ref()?)))
+            .filter_map(|(v, code)| Some((v, code, int_value(code)?)))
+            .collect();
+        for (i, (v, code, value)) in codes.iter().enumerate() {
+            if let Some((prev, _, _)) = codes[..i].iter().find(|(_, _, p)| p == value) {
+                let msg = format!("{} has the same code as {}", v.ident, prev.ident);
+                error(code, &msg);
+            }
+        }
+    }
+    if let Some(v) = variants.iter().find(|v| v.code.is_none()) {
+        if variants.iter().any(|v| v.code.is_some()) {
+            error(
+                &v.ident,
+                "either every variant or none of them must have a code",
+            );
+        } else if parsed.hash_by_code {
+            error(
+                &v.ident,
+                "hash_by_code requires every variant to have a code",
+            );
+        }
+    }
+    combine(errors).map_or(Ok(()), Err)
+}
+
+fn generate_display_only(parsed: ParsedErrors) -> syn::Result<TokenStream> {
+    validate(&parsed)?;
+    Ok(generate_display(&parsed, false))
+}
+
+/// the Display implementation, along with write_to if asked for. unit
+/// variants' messages come from the message constants if they are generated
+fn generate_display(parsed: &ParsedErrors, consts: bool) -> TokenStream {
+    let ParsedErrors {
+        ref ident,
+        ref generics,
+        ref variants,
+        write_to,
+        context_chain,
+        with_depth,
+        ref variant_name,
+        ref on_display,
+        static_table,
+        color,
+        pretty,
+        ref method_vis,
+        ref tracing_event,
+        ref strip_messages,
+        ..
+    } = *parsed;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // large enums look up their messages separately, so that variants shaped
+    // the same can share a single arm
+    let shared = variants.len() >= SHARED_ARM_VARIANTS;
+    // or-patterns need the same bindings with the same types everywhere
+    let shape = |v: &Variant, used: &dyn Fn(&Field) -> bool| {
+        let bound = v.fields.iter().enumerate().filter(|(_, f)| used(f));
+        let types = bound.map(|(fnum, f)| {
+            let ty = &f.ty;
+            format!("{fnum}: {}", quote!(#ty))
+        });
+        types.collect::<Vec<_>>().join(", ")
+    };
+    let mut table = vec![];
+    let arms = variants.iter().map(|v| {
+        let Variant { style, fields, .. } = v;
+        if v.transparent {
+            let (pat, get) = variant_pattern(&quote!(Self), v, |_| true);
+            let [ref inner] = get[..] else {
+                unreachable!("checked in validate")
+            };
+            let shape = shape(v, &|_| true);
+            return (pat, shape, vec![], vec![quote!(#inner)]);
+        }
+
+        let msg = variant_msg(v, variant_name);
+        let msg_parts = match msg {
+            Some(Msg::Str(ref msg)) => parse_msg(msg, fields),
+            _ => vec![],
+        };
+        let referenced: Vec<_> = msg_parts
+            .iter()
+            .filter_map(|part| match part {
+                MsgPart::Field(fnum) => Some(&fields[*fnum]),
+                MsgPart::Lit(_) => None,
+            })
+            .collect();
+        let interpolated = !referenced.is_empty();
+        let used = |f: &Field| f.shown() || referenced.iter().any(|r| std::ptr::eq(*r, f));
+        let (pat, get) = variant_pattern(&quote!(Self), v, used);
+        let shown: Vec<_> = fields
+            .iter()
+            .zip(&get)
+            .filter(|(f, _)| f.listed())
+            .collect();
+        let vals: Vec<_> = shown
+            .iter()
+            .map(|(field, fid)| field_value(field, fid))
+            .collect();
+        let mut field_fmt = vec![];
+        for (fnum, (field, _)) in shown.iter().enumerate() {
+            if fnum > 0 {
+                field_fmt.push(",".to_string());
+            }
+            let val = if field.quote { "\"{}\"" } else { "{}" };
+            let fo = match field.ident {
+                Some(ref fnm) => format!(" {fnm}: {val}"),
+                None => format!(" {val}"),
+            };
+            field_fmt.push(fo);
+        }
+
+        // templates can leave the message out
+        let looked_up = std::cell::Cell::new(false);
+        let push_msg = |fmt: &mut Vec<String>, args: &mut Vec<TokenStream>| {
+            let Some(ref msg) = msg else {
+                return;
+            };
+            if let Some(fnum) = v.prefix_field {
+                fmt.push("{}: ".to_string());
+                args.push(field_value(&fields[fnum], &get[fnum]));
+            }
+            match msg {
+                Msg::Str(_) if shared && !interpolated => {
+                    fmt.push("{}".to_string());
+                    args.push(quote!(__fox_msg));
+                    looked_up.set(true);
+                    return;
+                }
+                // written out from its parts, which have any doubled braces undone
+                Msg::Str(_) => {}
+                _ => {
+                    fmt.push("{}".to_string());
+                    args.push(quote!(#msg));
+                    return;
+                }
+            }
+            for part in &msg_parts {
+                match part {
+                    MsgPart::Lit(lit) => fmt.push(lit.replace('{', "{{").replace('}', "}}")),
+                    MsgPart::Field(fnum) => {
+                        let field = &fields[*fnum];
+                        let val = if field.quote { "\"{}\"" } else { "{}" };
+                        fmt.push(val.to_string());
+                        args.push(field_value(field, &get[*fnum]));
+                    }
+                }
+            }
+        };
+        let mut fmt = vec![];
+        let mut args = vec![];
+        let push_fields = |fmt: &mut Vec<String>, args: &mut Vec<TokenStream>| {
+            for fo in &field_fmt {
+                match fmt.is_empty() {
+                    true => fmt.push(fo.trim_start().to_string()),
+                    false => fmt.push(fo.clone()),
+                }
+            }
+            args.extend(vals.iter().cloned());
+        };
+        match v.template {
+            Some(ref template) => {
+                for part in template {
+                    match part {
+                        TemplatePart::Lit(lit) => {
+                            fmt.push(lit.replace('{', "{{").replace('}', "}}"));
+                        }
+                        TemplatePart::Msg => push_msg(&mut fmt, &mut args),
+                        TemplatePart::Fields => {
+                            // don't let the fields pick up leading space from
+                            // anything before them
+                            let start = fmt.len();
+                            push_fields(&mut fmt, &mut args);
+                            if start > 0 && start < fmt.len() {
+                                fmt[start] = fmt[start].trim_start().to_string();
+                            }
+                        }
+                    }
+                }
+            }
+            // a message that mentions fields already says everything
+            None if interpolated => push_msg(&mut fmt, &mut args),
+            None => {
+                if msg.is_some() {
+                    push_msg(&mut fmt, &mut args);
+                    if !matches!(style, Style::Unit) && (fields.is_empty() || !shown.is_empty()) {
+                        fmt.push(":".to_string());
+                    }
+                }
+                push_fields(&mut fmt, &mut args);
+            }
+        }
+        if let Some(ref terminator) = v.terminator {
+            fmt.push(terminator.replace('{', "{{").replace('}', "}}"));
+        }
+        if let (true, Some(Msg::Str(msg))) = (looked_up.get(), &msg) {
+            table.push((&v.ident, Msg::Str(msg.clone()).unescape()));
+        }
+
+        (pat, shape(v, &used), fmt, args)
+    });
+    let mut groups: Vec<(Vec<TokenStream>, _, _)> = Vec::with_capacity(variants.len());
+    let mut seen = std::collections::HashMap::<String, usize>::with_capacity(variants.len());
+    for (pat, shape, fmt, args) in arms {
+        let mut key = fmt.concat();
+        for arg in &args {
+            key.push('\0');
+            key.push_str(&arg.to_string());
+        }
+        key.push('\0');
+        key.push_str(&shape);
+        match seen.entry(key) {
+            std::collections::hash_map::Entry::Occupied(e) => groups[*e.get()].0.push(pat),
+            std::collections::hash_map::Entry::Vacant(e) => {
+                e.insert(groups.len());
+                groups.push((vec![pat], fmt, args));
+            }
+        }
+    }
+    let table = (!table.is_empty()).then(|| {
+        let (names, msgs): (Vec<_>, Vec<_>) = table.into_iter().unzip();
+        quote! {
+            #[allow(unreachable_patterns)]
+            let __fox_msg = match self {
+                #(Self::#names { .. } => #msgs,)*
+                _ => "",
+            };
+        }
+    });
+
+    let helpers = generate_helpers(variants);
+    let has_source = variants
+        .iter()
+        .any(|v| v.transparent || v.fields.iter().any(|f| f.source));
+    let count = with_depth.then(|| {
+        quote! {
+            let mut causes = 0usize;
+            let mut source = ::core::error::Error::source(self);
+            while let ::core::option::Option::Some(err) = source {
+                causes += 1;
+                source = ::core::error::Error::source(err);
+            }
+            match causes {
+                0 => {}
+                1 => f.write_str(" (1 cause)")?,
+                n => ::core::write!(f, " ({} causes)", n)?,
+            }
+        }
+    });
+    let walk = context_chain.then(|| {
+        quote! {
+            let mut source = ::core::error::Error::source(self);
+            let mut depth = 1;
+            while let ::core::option::Option::Some(err) = source {
+                ::core::write!(f, "\n{:indent$}caused by: {}", "", err, indent = depth * 2)?;
+                source = ::core::error::Error::source(err);
+                depth += 1;
+            }
+        }
+    });
+    let chain = ((context_chain || with_depth) && has_source).then(|| quote!(#count #walk));
+    let static_table = (static_table && !variants.is_empty()).then(|| {
+        let table = variants.iter().map(|v| {
+            let msg = variant_msg(v, variant_name).unwrap_or_default().unescape();
+            msg.suffix(v.terminator.as_deref().unwrap_or_default())
+        });
+        let len = variants.len();
+
+        // the trait name carries the message, like in generate_error_assert
+        quote! {
+            static MESSAGES: [&str; #len] = [#(#table),*];
+            trait StaticTableRequiresCopy {}
+            impl<T: ::core::marker::Copy> StaticTableRequiresCopy for T {}
+            fn assert<T: StaticTableRequiresCopy>() {}
+            assert::<#ident>();
+            f.write_str(MESSAGES[*self as usize])
+        }
+    });
+    // with only unit variants every message is a plain string, or one of the
+    // constants used by foxerror::message!, so picking one is all there is to it
+    let unit_msgs = (static_table.is_none()
+        && !variants.is_empty()
+        && variants
+            .iter()
+            .all(|v| matches!(v.style, Style::Unit) && v.template.is_none() && !v.transparent))
+    .then(|| {
+        let names = variants.iter().map(|v| &v.ident);
+        // without the constants the messages are written out in place
+        let msgs = variants.iter().map(|v| match consts {
+            true => {
+                let name = message_const(&v.ident);
+                quote!(Self::#name)
+            }
+            false => {
+                let msg = variant_msg(v, variant_name).unwrap_or_default().unescape();
+                let msg = msg.suffix(v.terminator.as_deref().unwrap_or_default());
+                quote!(#msg)
+            }
+        });
+        quote! {
+            match *self {
+                #(Self::#names => #msgs,)*
+            }
+        }
+    });
+    let pretty_chain = chain.clone();
+    // variants whose message may be replaced at runtime
+    let fmt_if = |formatter: bool| -> Vec<_> {
+        let write = match formatter {
+            true => quote!(::core::fmt::Display::fmt(&msg, f)),
+            false => quote!(::core::write!(f, "{}", msg)),
+        };
+        variants
+            .iter()
+            .filter_map(|v| {
+                let hook = v.fmt_if.as_ref()?;
+                let name = &v.ident;
+                Some(quote! {
+                    if let Self::#name { .. } = self {
+                        if let ::core::option::Option::Some(msg) = (#hook)(self) {
+                            return #write;
+                        }
+                    }
+                })
+            })
+            .collect()
+    };
+    let has_fmt_if = variants.iter().any(|v| v.fmt_if.is_some());
+    // the same body is generated for a Formatter and for write_to
+    let build = |formatter: bool| {
+        let arms = groups
+            .iter()
+            .map(|(pats, fmt, args)| write_arm(pats, fmt, args, formatter));
+        let body = match (&static_table, &chain, &variants[..]) {
+            (Some(table), _, _) => table.clone(),
+            (None, None, _) if unit_msgs.is_some() => quote!(f.write_str(#unit_msgs)),
+            (None, None, []) => quote!(match *self {}),
+            (None, Some(chain), _) => quote! {
+                #table
+                match self {
+                    #(#arms,)*
+                }?;
+                #chain
+                ::core::result::Result::Ok(())
+            },
+            (None, None, _) => quote! {
+                #table
+                match self {
+                    #(#arms,)*
+                }
+            },
+        };
+        let fmt_if = fmt_if(formatter);
+        quote!(#(#fmt_if)* #body)
+    };
+    let write_to_impl = write_to.then(|| {
+        let vis = method_vis.get("write_to");
+        let body = build(false);
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// write the message shown by `Display` into any writer,
+                /// such as a fixed buffer
+                #vis fn write_to(
+                    &self,
+                    f: &mut (impl ::core::fmt::Write + ?::core::marker::Sized),
+                ) -> ::core::fmt::Result {
+                    #helpers
+                    #body
+                }
+            }
+        }
+    });
+    let body = build(true);
+    let write = quote!({ #helpers #body });
+    // a Formatter can pad plain strings, which write_to has no way to do
+    let body = match unit_msgs {
+        Some(ref msgs) if !has_fmt_if => quote!(f.pad(#msgs)),
+        _ => write.clone(),
+    };
+    let pretty_arms: Vec<_> = variants
+        .iter()
+        .filter(|_| pretty)
+        .filter_map(|v| generate_pretty_arm(v, variant_name))
+        .collect();
+    let display_fmt_if = fmt_if(true);
+    let body = match pretty_arms[..] {
+        [] => body,
+        _ => quote! {
+            if f.alternate() {
+                #helpers
+                #(#display_fmt_if)*
+                #[allow(unreachable_patterns)]
+                match self {
+                    #(#pretty_arms,)*
+                    _ => return #write,
+                }?;
+                #pretty_chain
+                return ::core::result::Result::Ok(());
+            }
+            #body
+        },
+    };
+    // Display can't tell whether it's writing to a terminal, so colors are
+    // opt-in using the alternate flag
+    let body = match color {
+        true => quote! {
+            let write = |f: &mut ::core::fmt::Formatter<'_>| -> ::core::fmt::Result { #body };
+            if !f.alternate() {
+                return write(f);
+            }
+            f.write_str("\x1b[31m")?;
+            write(f)?;
+            f.write_str("\x1b[0m")
+        },
+        false => body,
+    };
+    // the event only names the variant, as rendering the message for it
+    // would run any fmt_if hooks a second time
+    let events: Vec<_> = variants
+        .iter()
+        .filter_map(|v| {
+            let level = v.tracing_level.as_ref().or(tracing_event.as_ref())?;
+            let name = &v.ident;
+            let variant = unraw(name);
+            let key = v.key.as_ref().map(|key| quote!(key = #key,));
+            Some(quote! {
+                Self::#name { .. } => ::tracing::event!(
+                    ::tracing::Level::#level,
+                    variant = #variant,
+                    #key
+                    "error displayed"
+                )
+            })
+        })
+        .collect();
+    let body = match events[..] {
+        [] => body,
+        _ => quote! {
+            #[allow(unreachable_patterns)]
+            match self {
+                #(#events,)*
+                _ => {}
+            }
+            #body
+        },
+    };
+    let on_display = on_display
+        .as_ref()
+        .filter(|_| !variants.is_empty())
+        .map(|hook| {
+            let names = variants.iter().map(|v| &v.ident);
+            let strs = variants.iter().map(|v| unraw(&v.ident));
+            quote! {
+                #hook(match self {
+                    #(Self::#names { .. } => #strs,)*
+                });
+            }
+        });
+
+    let display = quote! {
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #on_display
+                #body
+            }
+        }
+
+        #write_to_impl
+    };
+    // the messages have to be left out of codegen entirely, since being
+    // unused doesn't keep them out of the binary
+    match strip_messages {
+        Some(pred) => {
+            let stripped = generate_stripped_display(parsed);
+            quote! {
+                #[cfg(not(#pred))]
+                const _: () = { #display };
+                #[cfg(#pred)]
+                const _: () = { #stripped };
+            }
+        }
+        None => display,
+    }
+}
+
+/// a Display implementation writing only the code or name of each variant
+fn generate_stripped_display(parsed: &ParsedErrors) -> TokenStream {
+    let ParsedErrors {
+        ref ident,
+        ref generics,
+        ref variants,
+        write_to,
+        ref method_vis,
+        ..
+    } = *parsed;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let names = variants.iter().map(|v| &v.ident);
+    let body = if variants.is_empty() {
+        quote!(match *self {})
+    } else if variants.iter().all(|v| v.code.is_some()) {
+        let codes = variants.iter().map(|v| &v.code);
+        quote! {
+            ::core::write!(f, "{}", match self {
+                #(Self::#names { .. } => #codes,)*
+            })
+        }
+    } else {
+        let strs = variants.iter().map(|v| unraw(&v.ident));
+        quote! {
+            f.write_str(match self {
+                #(Self::#names { .. } => #strs,)*
+            })
+        }
+    };
+    let write_to_impl = write_to.then(|| {
+        let vis = method_vis.get("write_to");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// write the message shown by `Display` into any writer,
+                /// such as a fixed buffer
+                #vis fn write_to(
+                    &self,
+                    f: &mut (impl ::core::fmt::Write + ?::core::marker::Sized),
+                ) -> ::core::fmt::Result {
+                    #body
+                }
+            }
+        }
+    });
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+
+        #write_to_impl
+    }
+}
+
+fn generate(parsed: ParsedErrors) -> syn::Result<TokenStream> {
+    validate(&parsed)?;
+    let display = generate_display(&parsed, parsed.message_consts);
+    let ParsedErrors {
+        ident,
+        generics,
+        variants,
+        asserts,
+        impl_exit_code,
+        impl_from_display,
+        module,
+        context_chain: _,
+        with_depth: _,
+        display_fields,
+        variant_index,
+        variants_count,
+        deny_duplicate_messages: _,
+        hash_by_code,
+        variant_name,
+        std_error_v2,
+        vis,
+        result_alias,
+        machine_readable,
+        serde,
+        write_to: _,
+        message_consts,
+        subsets,
+        subset_args,
+        krate,
+        traits_crate,
+        opaque,
+        into,
+        infallible_from,
+        track_caller,
+        on_display: _,
+        strict,
+        boxed,
+        error_trait,
+        error_kind,
+        error_meta,
+        additive,
+        static_table: _,
+        color: _,
+        pretty: _,
+        method_vis,
+        tracing_event: _,
+        strip_messages: _,
+    } = parsed;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let into = into
+        .map(|(target, with)| generate_into(&ident, &generics, &variants, &target, with.as_ref()));
+
+    let froms = variants.iter().filter_map(|v| {
+        if !v.from {
+            return None;
+        }
+        let Some(field) = v.fields.iter().find(|f| f.implicit.is_none()) else {
+            unreachable!("checked in validate")
+        };
+        let field = &field.ty;
+        let name = &v.ident;
+        let vals = v.fields.iter().map(|f| match f.implicit {
+            Some(ref init) => init.clone(),
+            None => quote!(inner),
+        });
+        // lets implicit fields find out where the conversion happened
+        let track_caller = (track_caller || v.fields.len() > 1).then(|| quote!(#[track_caller]));
+
+        Some(quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::From<#field> for #ident #ty_generics #where_clause {
+                #track_caller
+                fn from(inner: #field) -> Self {
+                    Self::#name(#(#vals),*)
+                }
+            }
+        })
+    });
+
+    // an enum with no variants can't be constructed either
+    let infallible_from = (infallible_from || variants.is_empty()).then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::From<::core::convert::Infallible> for #ident #ty_generics #where_clause {
+                fn from(x: ::core::convert::Infallible) -> Self {
+                    match x {}
+                }
+            }
+        }
+    });
+
+    let other = variants.iter().find(|v| v.other).map(|v| {
+        let name = &v.ident;
+        let [ref field] = v.fields[..] else {
+            unreachable!("checked in validate")
+        };
+        let ty = &field.ty;
+        let vis = method_vis.get("other");
+        let track_caller = track_caller.then(|| quote!(#[track_caller]));
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// wrap any other error
+                #track_caller
+                #vis fn other(err: impl ::core::convert::Into<#ty>) -> Self {
+                    Self::#name(::core::convert::Into::into(err))
+                }
+            }
+        }
+    });
+
+    // transparent variants borrow their message from the inner error
+    let strict = variants
+        .iter()
+        .filter(|v| strict && v.msg.is_none() && !v.transparent)
+        .map(|v| {
+            let msg = format!("{} has no message, add a doc comment or msg", v.ident);
+            syn::Error::new(v.ident.span(), msg).to_compile_error()
+        });
+    let helpers = generate_helpers(&variants);
+    let has_source = variants
+        .iter()
+        .any(|v| v.transparent || v.fields.iter().any(|f| f.source));
+    let asserts = asserts
+        .into_iter()
+        .map(|bounds| generate_assert(&ident, &generics, bounds));
+    let error_asserts = variants.iter().flat_map(|v| {
+        v.fields
+            .iter()
+            .filter_map(|f| match (v.transparent, f.source) {
+                (true, _) => Some(generate_error_assert(&generics, &f.ty, "Transparent")),
+                (false, true) => Some(generate_error_assert(&generics, &f.ty, "Source")),
+                (false, false) => None,
+            })
+    });
+
+    let has_exit_code = impl_exit_code || variants.iter().any(|v| v.exit_code.is_some());
+    let exit_code = has_exit_code.then(|| {
+        let arms = variants.iter().filter_map(|v| {
+            let name = &v.ident;
+            let code = v.exit_code.as_ref()?;
+            Some(quote!(Self::#name { .. } => #code))
+        });
+
+        let vis = method_vis.get("exit_code");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the exit code to use when exiting due to this error
+                #vis fn exit_code(&self) -> i32 {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#arms,)*
+                        _ => 1,
+                    }
+                }
+            }
+        }
+    });
+
+    let sources: Vec<_> = variants
+        .iter()
+        .filter_map(|v| {
+            if v.transparent {
+                let (pat, get) = variant_pattern(&quote!(Self), v, |_| true);
+                // a method call, so boxed errors are reached through deref
+                return Some(quote!(#pat => {
+                    use ::core::error::Error as _;
+                    #(#get)*.source()
+                }));
+            }
+            let fnum = v.fields.iter().position(|f| f.source)?;
+            let (pat, get) = variant_pattern(&quote!(Self), v, |f| f.source);
+            let inner = &get[fnum];
+            // a borrowed error would otherwise be taken as an error itself,
+            // which isn't 'static
+            let ty = &v.fields[fnum].ty;
+            let inner = match ty {
+                syn::Type::Reference(_) => quote!((**#inner)),
+                _ => quote!(#inner),
+            };
+            // point at the field if it turns out not to be an error
+            let call = quote_spanned!(ty.span()=> #inner.__fox_as_dyn_error());
+            Some(quote! {
+                #pat => ::core::option::Option::Some(#call)
+            })
+        })
+        .collect();
+    // for old code still calling the deprecated description
+    let description = (cfg!(feature = "legacy") && !variants.is_empty()).then(|| {
+        let arms = variants.iter().map(|v| {
+            let name = &v.ident;
+            let msg = match v {
+                Variant {
+                    style: Style::Unit,
+                    template: None,
+                    ..
+                } => {
+                    let msg = variant_msg(v, &variant_name).unwrap_or_default().unescape();
+                    msg.suffix(v.terminator.as_deref().unwrap_or_default())
+                }
+                _ => Msg::Str(unraw(name)),
+            };
+            quote!(Self::#name { .. } => #msg)
+        });
+        quote! {
+            #[allow(deprecated)]
+            fn description(&self) -> &str {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    });
+    let source = has_source.then(|| {
+        quote! {
+            fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                trait __FoxAsDynError {
+                    fn __fox_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static);
+                }
+
+                impl<T: ::core::error::Error + 'static> __FoxAsDynError for T {
+                    fn __fox_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) {
+                        self
+                    }
+                }
+
+                impl __FoxAsDynError for dyn ::core::error::Error + 'static {
+                    fn __fox_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) {
+                        self
+                    }
+                }
+
+                impl __FoxAsDynError for dyn ::core::error::Error + ::core::marker::Send + 'static {
+                    fn __fox_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) {
+                        self
+                    }
+                }
+
+                impl __FoxAsDynError
+                    for dyn ::core::error::Error + ::core::marker::Send + ::core::marker::Sync + 'static
+                {
+                    fn __fox_as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) {
+                        self
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                match self {
+                    #(#sources,)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    });
+    // source and description only exist on core::error::Error
+    let error_impl = match error_trait {
+        Some(path) => quote! {
+            #[automatically_derived]
+            impl #impl_generics #path for #ident #ty_generics #where_clause {}
+        },
+        None => quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::error::Error for #ident #ty_generics #where_clause {
+                #source
+
+                #description
+            }
+        },
+    };
+
+    // items that aren't implementations on the enum itself
+    let codes: Option<Vec<_>> = variants.iter().map(|v| v.code.as_ref()).collect();
+    let codes = codes.filter(|_| !variants.is_empty());
+    let has_codes = codes.is_some();
+    let mut aux: Vec<TokenStream> = vec![];
+    let aux_vis = match (&module, &vis) {
+        (Some(_), syn::Visibility::Inherited) => quote!(pub(super)),
+        _ => quote!(#vis),
+    };
+    if let Some(alias) = result_alias {
+        aux.push(generate_result_alias(&aux_vis, &ident, &generics, &alias));
+    }
+    let info = syn::Ident::new(&format!("{ident}Info"), ident.span());
+    let kind = syn::Ident::new(&format!("{ident}Kind"), ident.span());
+    if machine_readable {
+        let serialize = serde.then(|| quote!(#[derive(::serde::Serialize)]));
+        aux.push(quote! {
+            /// a machine-readable summary of an error
+            #[derive(::core::fmt::Debug, ::core::clone::Clone)]
+            #serialize
+            #aux_vis struct #info {
+                /// the name of the variant
+                pub variant: &'static str,
+                /// the message as written, without the fields and with any
+                /// `{field}` placeholders left in
+                pub message: &'static str,
+                /// the error code, if the variants have them
+                pub code: ::core::option::Option<i32>,
+                /// the names and displayed values of the fields
+                pub fields: ::std::vec::Vec<(&'static str, ::std::string::String)>,
+            }
+        });
+    }
+    for subset in &subsets {
+        aux.push(generate_subset(
+            subset,
+            &aux_vis,
+            &ident,
+            &generics,
+            &variants,
+            &subset_args,
+            &krate,
+        ));
+    }
+    if let Some(ref opaque) = opaque {
+        aux.push(generate_opaque(
+            opaque,
+            &ident,
+            &generics,
+            has_exit_code,
+            has_codes,
+            error_kind.then_some(&kind),
+            &method_vis,
+        ));
+    }
+    if let Some(boxed) = boxed {
+        aux.push(generate_boxed(
+            &aux_vis,
+            &boxed,
+            &ident,
+            &generics,
+            &variants,
+            &method_vis,
+            track_caller,
+        ));
+    }
+    if error_kind {
+        aux.push(generate_kind_enum(
+            &aux_vis,
+            &kind,
+            &variants,
+            codes.as_ref(),
+        ));
+    }
+    if let Some(ref parse_error) = impl_from_display {
+        let msg = format!("no variant of {ident} has this message");
+        aux.push(quote! {
+            /// the error returned when a message doesn't match any variant
+            #[derive(
+                ::core::fmt::Debug,
+                ::core::clone::Clone,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+            )]
+            #aux_vis struct #parse_error;
+
+            #[automatically_derived]
+            impl ::core::fmt::Display for #parse_error {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(#msg)
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::error::Error for #parse_error {}
+        });
+    }
+    let aux_path = |item: &syn::Ident| match module {
+        Some((_, ref name)) => quote!(#name::#item),
+        None => quote!(#item),
+    };
+    let info_path = aux_path(&info);
+    let kind_path = aux_path(&kind);
+    let from_str = impl_from_display.map(|parse_error| {
+        generate_from_str(
+            &ident,
+            &generics,
+            &variants,
+            &variant_name,
+            &aux_path(&parse_error),
+        )
+    });
+    let aux = match module {
+        Some((vis, name)) => quote! {
+            #vis mod #name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #(#aux)*
+            }
+        },
+        None => quote!(#(#aux)*),
+    };
+
+    let error_code = codes.map(|codes| {
+        let names = variants.iter().map(|v| &v.ident);
+        let vis = method_vis.get("error_code");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the code identifying this error
+                #vis fn error_code(&self) -> i32 {
+                    match self {
+                        #(Self::#names { .. } => #codes,)*
+                    }
+                }
+            }
+        }
+    });
+
+    let hash_by_code = hash_by_code.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::hash::Hash for #ident #ty_generics #where_clause {
+                fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                    ::core::hash::Hash::hash(&self.error_code(), state)
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics ::core::cmp::PartialEq for #ident #ty_generics #where_clause {
+                fn eq(&self, other: &Self) -> bool {
+                    self.error_code() == other.error_code()
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics ::core::cmp::Eq for #ident #ty_generics #where_clause {}
+        }
+    });
+
+    let display_fields = display_fields.then(|| {
+        let arms = variants.iter().map(|v| {
+            let (pat, pairs) = field_pairs(v);
+            quote!(#pat => ::std::vec![#(#pairs),*])
+        });
+
+        let vis = method_vis.get("display_fields");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the names and displayed values of this variant's fields
+                #vis fn display_fields(&self) -> ::std::vec::Vec<(&'static str, ::std::string::String)> {
+                    #helpers
+                    match self {
+                        #(#arms,)*
+                    }
+                }
+            }
+        }
+    });
+
+    // lets foxerror::message! find the messages of unit variants
+    let (message_names, messages): (Vec<_>, Vec<_>) = variants
+        .iter()
+        .filter(|v| matches!(v.style, Style::Unit) && v.template.is_none() && !v.transparent)
+        .map(|v| {
+            let msg = variant_msg(v, &variant_name).unwrap_or_default().unescape();
+            let msg = msg.suffix(v.terminator.as_deref().unwrap_or_default());
+            (message_const(&v.ident), msg)
+        })
+        .unzip();
+    let messages = (message_consts && !messages.is_empty()).then(|| {
+        quote! {
+            #[allow(non_upper_case_globals)]
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #(
+                    #[doc(hidden)]
+                    pub const #message_names: &'static str = #messages;
+                )*
+            }
+        }
+    });
+
+    let variant_index = variant_index.then(|| {
+        let names = variants.iter().map(|v| &v.ident);
+        let indexes = 0..variants.len();
+        let vis = method_vis.get("variant_index");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the position of this variant in the enum, starting at 0
+                #vis fn variant_index(&self) -> usize {
+                    match *self {
+                        #(Self::#names { .. } => #indexes,)*
+                    }
+                }
+            }
+        }
+    });
+
+    let variants_count = variants_count.then(|| {
+        let count = variants.len();
+        let vis = method_vis.get("variants_count");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the number of variants in the enum
+                #vis const VARIANTS_COUNT: usize = #count;
+            }
+        }
+    });
+
+    let keys: Option<Vec<_>> = variants.iter().map(|v| v.key.as_ref()).collect();
+    let message_key = keys.filter(|_| !variants.is_empty()).map(|keys| {
+        let names = variants.iter().map(|v| &v.ident);
+        let vis = method_vis.get("message_key");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the key for looking up a translation of the message
+                #vis fn message_key(&self) -> &'static str {
+                    match *self {
+                        #(Self::#names { .. } => #keys,)*
+                    }
+                }
+            }
+        }
+    });
+
+    let leak = variants
+        .iter()
+        .any(|v| v.as_static)
+        .then(|| generate_leak(&ident, &generics, &variants, method_vis.get("leak")));
+
+    // tracks the unstable `<dyn Error>::sources` from error_iter, which can't
+    // be overridden since it is not a trait method
+    let std_error_v2 = std_error_v2.then(|| {
+        let vis = method_vis.get("sources");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// an iterator over this error and its chain of sources
+                #vis fn sources(&self) -> impl ::core::iter::Iterator<Item = &(dyn ::core::error::Error + 'static)> {
+                    let first: &(dyn ::core::error::Error + 'static) = self;
+                    ::core::iter::successors(::core::option::Option::Some(first), |e| ::core::error::Error::source(*e))
+                }
+            }
+        }
+    });
+
+    let machine_readable = machine_readable.then(|| {
+        let code = match has_codes {
+            true => quote!(::core::option::Option::Some(self.error_code())),
+            false => quote!(::core::option::Option::None),
+        };
+        let arms = variants.iter().map(|v| {
+            let (pat, pairs) = field_pairs(v);
+            let variant = unraw(&v.ident);
+            let message = variant_msg(v, &variant_name).unwrap_or_default();
+            quote!(#pat => (#variant, #message, ::std::vec![#(#pairs),*]))
+        });
+
+        let vis = method_vis.get("to_info");
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// a machine-readable summary of this error
+                #vis fn to_info(&self) -> #info_path {
+                    #helpers
+                    let (variant, message, fields) = match self {
+                        #(#arms,)*
+                    };
+                    #info_path {
+                        variant,
+                        message,
+                        code: #code,
+                        fields,
+                    }
+                }
+            }
+        }
+    });
+
+    let serde = serde.then(|| generate_serialize(&ident, &generics, &variants));
+
+    let error_kind = error_kind.then(|| {
+        let names: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+        let body = match names[..] {
+            [] => quote!(match *err {}),
+            _ => quote! {
+                match err {
+                    #(#ident::#names { .. } => Self::#names,)*
+                }
+            },
+        };
+
+        let vis = method_vis.get("kind");
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::From<&#ident #ty_generics> for #kind_path #where_clause {
+                fn from(err: &#ident #ty_generics) -> Self {
+                    #body
+                }
+            }
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// the kind of this error
+                #vis fn kind(&self) -> #kind_path {
+                    ::core::convert::Into::into(self)
+                }
+            }
+        }
+    });
+
+    let error_meta = error_meta.then(|| {
+        let names: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+        let strs = names.iter().map(|n| unraw(n));
+        let severities = variants.iter().map(|v| match v.severity {
+            Some(ref severity) => quote!(#traits_crate::Severity::#severity),
+            None => quote!(::core::default::Default::default()),
+        });
+        let code = match has_codes {
+            true => quote!(::core::option::Option::Some(self.error_code())),
+            false => quote!(::core::option::Option::None),
+        };
+
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #traits_crate::ErrorMeta for #ident #ty_generics #where_clause {
+                fn name(&self) -> &'static str {
+                    match self {
+                        #(Self::#names { .. } => #strs,)*
+                    }
+                }
+
+                fn code(&self) -> ::core::option::Option<i32> {
+                    #code
+                }
+
+                fn severity(&self) -> #traits_crate::Severity {
+                    match self {
+                        #(Self::#names { .. } => #severities,)*
+                    }
+                }
+            }
+        }
+    });
+
+    let additive = additive.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::ops::Add for #ident #ty_generics #where_clause {
+                type Output = #traits_crate::MultiError<Self>;
+
+                fn add(self, rhs: Self) -> Self::Output {
+                    #traits_crate::MultiError(::std::vec![self, rhs])
+                }
+            }
+        }
+    });
+
+    let termination = impl_exit_code.then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::std::process::Termination for #ident #ty_generics #where_clause {
+                fn report(self) -> ::std::process::ExitCode {
+                    ::std::eprintln!("{}", self);
+                    // codes that don't fit still have to report failure
+                    let code = match <u8 as ::core::convert::TryFrom<i32>>::try_from(self.exit_code()) {
+                        ::core::result::Result::Ok(0) => 1,
+                        code => code.unwrap_or(u8::MAX),
+                    };
+                    <::std::process::ExitCode as ::core::convert::From<u8>>::from(code)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #display
+
+        #error_impl
+
+        #(#froms)*
+
+        #infallible_from
+
+        #other
+
+        #into
+
+        #exit_code
+
+        #error_code
+
+        #hash_by_code
+
+        #display_fields
+
+        #variant_index
+
+        #variants_count
+
+        #leak
+
+        #from_str
+
+        #message_key
+
+        #messages
+
+        #machine_readable
+
+        #serde
+
+        #error_kind
+
+        #error_meta
+
+        #additive
+
+        #std_error_v2
+
+        #termination
+
+        #(#asserts)*
+
+        #(#error_asserts)*
+
+        #(#strict)*
+
+        #aux
+    })
+}
+
+struct FoxVariant {
+    msg: Option<syn::LitStr>,
+    variant: syn::Variant,
+}
+
+impl Parse for FoxVariant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = input.call(syn::Attribute::parse_outer)?;
+        let msg = if input.peek(syn::LitStr) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let mut variant: syn::Variant = input.parse()?;
+        attrs.append(&mut variant.attrs);
+        variant.attrs = attrs;
+        Ok(Self { msg, variant })
+    }
+}
+
+struct FoxInput {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+    generics: syn::Generics,
+    variants: Vec<FoxVariant>,
+}
+
+impl Parse for FoxInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let ident = input.parse()?;
+        let mut generics: syn::Generics = input.parse()?;
+        generics.where_clause = input.parse()?;
+        let content;
+        syn::braced!(content in input);
+        let variants = content
+            .parse_terminated(FoxVariant::parse, Token![,])?
+            .into_iter()
+            .collect();
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            generics,
+            variants,
+        })
+    }
+}
+
+fn crate_path(attrs: &[syn::Attribute]) -> syn::Result<TokenStream> {
+    let args = parse_attrs(attrs)?;
+    Ok(match args.iter().rfind(|a| a.ident == "crate") {
+        Some(a) => {
+            let krate = a.value()?;
+            quote!(#krate)
+        }
+        None => quote!(::foxerror),
+    })
+}
+
+fn traits_path(args: &[AttrArg]) -> syn::Result<TokenStream> {
+    Ok(match args.iter().rfind(|a| a.ident == "traits_crate") {
+        Some(a) => {
+            let path = a.path()?;
+            quote!(#path)
+        }
+        None => quote!(::foxerror_traits),
+    })
+}
+
+fn generate_fox(input: FoxInput) -> syn::Result<TokenStream> {
+    let FoxInput {
+        attrs,
+        vis,
+        ident,
+        generics,
+        variants,
+    } = input;
+    let where_clause = &generics.where_clause;
+    let krate = crate_path(&attrs)?;
+
+    let variants = variants.into_iter().map(|v| {
+        let FoxVariant { msg, mut variant } = v;
+        gate_variant(&mut variant)?;
+        // keep inline messages visible in rustdoc too
+        let documented = variant.attrs.iter().any(|a| parse_attr_doc(a).is_some());
+        let doc = msg
+            .as_ref()
+            .filter(|_| !documented)
+            .map(|msg| quote!(#[doc = #msg]));
+        let msg = msg.map(|msg| quote!(#[err(msg = #msg)]));
+        Ok(quote!(#doc #msg #variant))
+    });
+    let variants = collect_all(variants)?;
+
+    Ok(quote! {
+        #[derive(::core::fmt::Debug, #krate::FoxError)]
+        #(#attrs)*
+        #vis enum #ident #generics #where_clause {
+            #(#variants,)*
+        }
+    })
+}
+
+fn generate_error_attr(args: TokenStream, mut item: DeriveInput) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        item.attrs.push(syn::parse_quote!(#[err(#args)]));
+    }
+    let krate = crate_path(&item.attrs)?;
+    let enum_args = parse_attrs(&item.attrs)?;
+    if let syn::Data::Enum(ref mut body) = item.data {
+        for v in &mut body.variants {
+            gate_variant(v)?;
+        }
+    }
+
+    if arg_flag(&enum_args, "implicit_location")? {
+        let body = match item.data {
+            syn::Data::Enum(ref mut body) => body,
+            syn::Data::Struct(ref s) => {
+                return Err(syn::Error::new_spanned(
+                    s.struct_token,
+                    "only enums are supported",
+                ))
+            }
+            syn::Data::Union(ref u) => {
+                return Err(syn::Error::new_spanned(
+                    u.union_token,
+                    "only enums are supported",
+                ))
+            }
+        };
+        let location: syn::Field = syn::parse_quote! {
+            #[err(implicit = ::core::panic::Location::caller())]
+            location: &'static ::core::panic::Location<'static>
+        };
+        for v in &mut body.variants {
+            match v.fields {
+                syn::Fields::Named(ref mut fields) => {
+                    let taken = fields.named.iter().any(|f| f.ident == location.ident);
+                    if !taken {
+                        fields.named.push(location.clone());
+                    }
+                }
+                syn::Fields::Unnamed(ref mut fields) => fields.unnamed.push(syn::Field {
+                    ident: None,
+                    colon_token: None,
+                    ..location.clone()
+                }),
+                syn::Fields::Unit => (),
+            }
+        }
+    }
+
+    let has_debug = item.attrs.iter().any(|a| {
+        a.path().is_ident("derive")
+            && a.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+                .is_ok_and(|paths| {
+                    paths
+                        .iter()
+                        .any(|p| p.segments.last().unwrap().ident == "Debug")
+                })
+    });
+    let debug = (!has_debug).then(|| quote!(::core::fmt::Debug,));
+
+    Ok(quote! {
+        #[derive(#debug #krate::FoxError)]
+        #item
+    })
+}
+
+fn result_ok_type(ty: &syn::Type) -> syn::Result<syn::Type> {
+    let syn::Type::Path(syn::TypePath { ref path, .. }) = ty else {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "context functions must return a Result",
+        ));
+    };
+    if let Some(syn::PathArguments::AngleBracketed(ref args)) =
+        path.segments.last().map(|s| &s.arguments)
+    {
+        // closures can't name impl Trait types, so those are left to inference
+        if let Some(syn::GenericArgument::Type(ok)) = args.args.first() {
+            if !has_impl(quote!(#ok)) {
+                return Ok(ok.clone());
+            }
+        }
+    }
+    Ok(syn::parse_quote!(_))
+}
+
+fn has_impl(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|t| match t {
+        proc_macro2::TokenTree::Ident(i) => i == "impl",
+        proc_macro2::TokenTree::Group(g) => has_impl(g.stream()),
+        _ => false,
+    })
+}
+
+/// a function split into just the parts context needs, which saves parsing
+/// everything in it
+struct ContextFn {
+    sig: TokenStream,
+    asyncness: bool,
+    output: Option<syn::Type>,
+    body: proc_macro2::Group,
+}
+
+impl Parse for ContextFn {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        use proc_macro2::{Delimiter, TokenTree};
+
+        let mut tokens: Vec<TokenTree> = vec![];
+        while !input.is_empty() {
+            tokens.push(input.parse()?);
+        }
+        let body = match tokens.pop() {
+            Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => g,
+            _ => return Err(input.error("expected a function with a body")),
+        };
+        let is_ident = |t: &TokenTree, name: &str| matches!(t, TokenTree::Ident(i) if i == name);
+        let Some(fn_at) = tokens.iter().position(|t| is_ident(t, "fn")) else {
+            return Err(input.error("expected a function"));
+        };
+        let asyncness = tokens[..fn_at].iter().any(|t| is_ident(t, "async"));
+        let is_arrow = |w: &[TokenTree]| match w {
+            [TokenTree::Punct(a), TokenTree::Punct(b), ..] => {
+                a.as_char() == '-'
+                    && a.spacing() == proc_macro2::Spacing::Joint
+                    && b.as_char() == '>'
+            }
+            _ => false,
+        };
+        // the arguments are the first parentheses outside the generics,
+        // which can hold arrows of their own, like in Fn() -> u8
+        let mut depth = 0usize;
+        let mut at = fn_at + 1;
+        let args_at = loop {
+            match tokens.get(at) {
+                None => return Err(input.error("expected the function's arguments")),
+                _ if is_arrow(&tokens[at..]) => at += 1,
+                Some(TokenTree::Punct(p)) if p.as_char() == '<' => depth += 1,
+                Some(TokenTree::Punct(p)) if p.as_char() == '>' => {
+                    depth = depth.saturating_sub(1);
+                }
+                Some(TokenTree::Group(g))
+                    if depth == 0 && g.delimiter() == Delimiter::Parenthesis =>
+                {
+                    break at;
+                }
+                _ => {}
+            }
+            at += 1;
+        };
+        // everything after -> and before any where clause
+        let arrow = (args_at + 1..tokens.len()).find(|&at| is_arrow(&tokens[at..]));
+        let output = match arrow {
+            Some(at) => {
+                let ty = tokens[at + 2..]
+                    .iter()
+                    .take_while(|t| !is_ident(t, "where"))
+                    .cloned()
+                    .collect::<TokenStream>();
+                Some(syn::parse2(ty)?)
+            }
+            None => None,
+        };
+        Ok(Self {
+            sig: tokens.into_iter().collect(),
+            asyncness,
+            output,
+            body,
+        })
+    }
+}
+
+/// what context wraps errors in, along with any arguments after it
+struct ContextArgs {
+    wrap: syn::Expr,
+    traits_crate: TokenStream,
+}
+
+impl Parse for ContextArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let wrap = parse_expr(input)?;
+        let mut args = vec![];
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            args = input.parse::<AttrArgs>()?.0;
+        }
+        if let Some(a) = args.iter().find(|a| a.ident != "traits_crate") {
+            let msg = format!(
+                "unknown argument `{}`, context only takes traits_crate",
+                a.ident
+            );
+            return Err(syn::Error::new_spanned(&a.ident, msg));
+        }
+        Ok(Self {
+            wrap,
+            traits_crate: traits_path(&args)?,
+        })
+    }
+}
+
+/// a struct variant along with the field the error goes in, like
+/// `Error::LoadConfig { source }`
+struct ContextField {
+    path: syn::Path,
+    field: syn::Ident,
+}
+
+impl Parse for ContextField {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let content;
+        let brace = syn::braced!(content in input);
+        let field = content
+            .parse()
+            .ok()
+            .filter(|_| content.parse::<Option<Token![,]>>().is_ok() && content.is_empty());
+        let Some(field) = field else {
+            return Err(syn::Error::new(
+                brace.span.join(),
+                "expected only the name of the field holding the error, like { source }",
+            ));
+        };
+        Ok(Self { path, field })
+    }
+}
+
+fn generate_context(args: ContextArgs, func: ContextFn) -> syn::Result<TokenStream> {
+    let ContextArgs { wrap, traits_crate } = args;
+    let ContextFn {
+        sig,
+        asyncness,
+        output,
+        body,
+    } = func;
+    let Some(ty) = output else {
+        return Err(syn::Error::new_spanned(
+            &sig,
+            "context functions must return a Result",
+        ));
+    };
+    let ok = result_ok_type(&ty)?;
+    // a message wraps the errors in a Context, so anything goes in the body
+    let (wrap, err) = match wrap {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(msg),
+            ..
+        }) => (
+            quote! {
+                |source| ::core::convert::From::from(#traits_crate::Context { msg: #msg, source })
+            },
+            quote! {
+                ::std::boxed::Box<
+                    dyn ::core::error::Error + ::core::marker::Send + ::core::marker::Sync,
+                >
+            },
+        ),
+        // a struct variant, whichever way syn managed to parse it
+        wrap if matches!(
+            quote!(#wrap).into_iter().last(),
+            Some(proc_macro2::TokenTree::Group(g))
+                if g.delimiter() == proc_macro2::Delimiter::Brace
+        ) =>
+        {
+            let ContextField { path, field } = syn::parse2(quote!(#wrap))?;
+            (quote!(|#field| #path { #field }), quote!(_))
+        }
+        wrap => (quote!(#wrap), quote!(_)),
+    };
+
+    // a closure rather than a block, so ? and return in the body stop at
+    // the wrapping instead of leaving the function
+    let inner = if asyncness {
+        quote!(async move {
+            let result: ::core::result::Result<_, #err> = #body;
+            result
+        }.await)
+    } else {
+        quote!((move || -> ::core::result::Result<#ok, #err> #body)())
+    };
+
+    Ok(quote! {
+        #sig {
+            #inner.map_err(#wrap)
+        }
+    })
+}
+
+struct FoxUnion {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+    types: Vec<syn::TypePath>,
+}
+
+impl Parse for FoxUnion {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let mut types = vec![input.parse()?];
+        while input.parse::<Token![|]>().is_ok() {
+            types.push(input.parse()?);
+        }
+        input.parse::<Option<Token![;]>>()?;
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            types,
+        })
+    }
+}
+
+fn union_variant_name(ty: &syn::TypePath) -> syn::Ident {
+    let mut name = String::new();
+    for seg in &ty.path.segments {
+        let seg = seg.ident.to_string();
+        let mut chars = seg.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+    syn::Ident::new(&name, Span::call_site())
+}
+
+fn generate_fox_union(input: FoxUnion) -> syn::Result<TokenStream> {
+    let FoxUnion {
+        attrs,
+        vis,
+        ident,
+        types,
+    } = input;
+
+    let mut seen = vec![];
+    for ty in &types {
+        let key = quote!(#ty).to_string();
+        if seen.contains(&key) {
+            let msg = format!("{key} is listed more than once");
+            return Err(syn::Error::new_spanned(ty, msg));
+        }
+        seen.push(key);
+    }
+    let names = types.iter().map(union_variant_name);
+    let krate = crate_path(&attrs)?;
+
+    Ok(quote! {
+        #[derive(::core::fmt::Debug, #krate::FoxError)]
+        #(#attrs)*
+        #vis enum #ident {
+            #(
+                #[err(transparent, from)]
+                #names(#types),
+            )*
+        }
+    })
+}
+
+/// expand `#[derive(FoxError)]`
+pub fn derive(input: TokenStream) -> TokenStream {
+    let input = match syn::parse2::<DeriveInput>(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+    let fallback = generate_fallback(&input, true);
+    let output = parse_derive(input).and_then(generate);
+
+    output.unwrap_or_else(|err| {
+        let err = err.to_compile_error();
+        quote!(#err #fallback)
+    })
+}
+
+/// expand `#[derive(FoxErrorDisplay)]`
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let input = match syn::parse2::<DeriveInput>(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+    let fallback = generate_fallback(&input, false);
+    let output = parse_derive(input).and_then(generate_display_only);
+
+    output.unwrap_or_else(|err| {
+        let err = err.to_compile_error();
+        quote!(#err #fallback)
+    })
+}
+
+/// expand `fox!`
+pub fn fox(input: TokenStream) -> TokenStream {
+    syn::parse2(input)
+        .and_then(generate_fox)
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+/// expand `#[foxerror::error]`
+pub fn error(attr: TokenStream, item: TokenStream) -> TokenStream {
+    syn::parse2(item)
+        .and_then(|item| generate_error_attr(attr, item))
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+/// expand `#[foxerror::context]`
+pub fn context(attr: TokenStream, item: TokenStream) -> TokenStream {
+    syn::parse2(attr)
+        .and_then(|args| generate_context(args, syn::parse2(item)?))
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+/// expand `fox_union!`
+pub fn fox_union(input: TokenStream) -> TokenStream {
+    syn::parse2(input)
+        .and_then(generate_fox_union)
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+/// expand `message!`
+pub fn message(input: TokenStream) -> TokenStream {
+    syn::parse2(input)
+        .and_then(generate_message)
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,446 @@
+use super::*;
+
+fn parse(input: TokenStream) -> syn::Result<ParsedErrors> {
+    parse_derive(syn::parse2(input).unwrap())
+}
+
+fn parse_err(input: TokenStream) -> String {
+    match parse(input) {
+        Ok(_) => panic!("expected the input to be rejected"),
+        Err(err) => err.to_string(),
+    }
+}
+
+fn msg_str(v: &Variant, variant_name: &VariantName) -> Option<String> {
+    match variant_msg(v, variant_name)? {
+        Msg::Str(msg) => Some(msg),
+        _ => panic!("expected a string message"),
+    }
+}
+
+/// the parts of a message, with fields shown as `#n`
+fn parts(msg: &str, v: &Variant) -> Vec<String> {
+    parse_msg(msg, &v.fields)
+        .into_iter()
+        .map(|p| match p {
+            MsgPart::Lit(lit) => lit,
+            MsgPart::Field(fnum) => format!("#{fnum}"),
+        })
+        .collect()
+}
+
+#[test]
+fn parse_msg_splits_on_fields() {
+    let parsed = parse(quote! {
+        enum Error {
+            /// could not bind to {host}:{port} {not a field}
+            Bind { host: String, port: u16 },
+            /// port {0} is reserved
+            Reserved(u16),
+            /// bad port ({source})
+            BadPort(#[err(source)] std::num::ParseIntError),
+        }
+    })
+    .unwrap();
+    let [bind, reserved, bad_port] = &parsed.variants[..] else {
+        panic!("expected three variants");
+    };
+    let msg = msg_str(bind, &VariantName::Auto).unwrap();
+    assert_eq!(
+        parts(&msg, bind),
+        ["could not bind to ", "#0", ":", "#1", " {not a field}"]
+    );
+    let msg = msg_str(reserved, &VariantName::Auto).unwrap();
+    assert_eq!(parts(&msg, reserved), ["port ", "#0", " is reserved"]);
+    let msg = msg_str(bad_port, &VariantName::Auto).unwrap();
+    assert_eq!(parts(&msg, bad_port), ["bad port (", "#0", ")"]);
+}
+
+#[test]
+fn check_placeholders_lists_fields() {
+    let err = parse_err(quote! {
+        enum Error {
+            /// could not bind to {hostname}:{port}
+            Bind { host: String, port: u16 },
+        }
+    });
+    assert_eq!(err, "no field named `hostname`, did you mean `{host}`?");
+
+    let err = parse_err(quote! {
+        enum Error {
+            /// could not bind to {address}
+            Bind { host: String, port: u16 },
+        }
+    });
+    assert_eq!(err, "no field named `address`, Bind has `{host}`, `{port}`");
+
+    let err = parse_err(quote! {
+        enum Error {
+            /// port {1} is reserved
+            Reserved(u16),
+        }
+    });
+    assert_eq!(err, "`{1}` is out of range, Reserved only has `{0}`");
+}
+
+#[test]
+fn check_keys_merges_attributes() {
+    let parsed = parse(quote! {
+        enum Error {
+            /// bad port
+            #[err(code = 1)]
+            #[err(exit_code = 2)]
+            BadPort(u16),
+        }
+    })
+    .unwrap();
+    let v = &parsed.variants[0];
+    assert!(v.code.is_some());
+    assert!(v.exit_code.is_some());
+}
+
+#[test]
+fn check_keys_rejects_duplicates() {
+    let err = parse_err(quote! {
+        enum Error {
+            /// bad port
+            #[err(code = 1)]
+            #[err(code = 2)]
+            BadPort(u16),
+        }
+    });
+    assert_eq!(err, "`code` is given more than once");
+}
+
+#[test]
+fn check_keys_suggests_similar_keys() {
+    let err = parse_err(quote! {
+        enum Error {
+            #[err(mgs = "bad port")]
+            BadPort(u16),
+        }
+    });
+    assert_eq!(err, "unknown argument `mgs`, did you mean `msg`?");
+
+    let err = parse_err(quote! {
+        #[err(transparent)]
+        enum Error {
+            /// bad port
+            BadPort(u16),
+        }
+    });
+    assert_eq!(err, "`transparent` can only be used on variants");
+}
+
+#[test]
+fn variant_msg_fallback_order() {
+    let parsed = parse(quote! {
+        enum Error {
+            /// from the doc comment
+            #[err(msg = "from msg")]
+            Both,
+            /// from the doc comment
+            Doc,
+            Neither,
+        }
+    })
+    .unwrap();
+    let [both, doc, neither] = &parsed.variants[..] else {
+        panic!("expected three variants");
+    };
+    let auto = VariantName::Auto;
+    assert_eq!(msg_str(both, &auto).as_deref(), Some("from msg"));
+    assert_eq!(msg_str(doc, &auto).as_deref(), Some("from the doc comment"));
+    assert_eq!(msg_str(neither, &auto).as_deref(), Some("Neither"));
+    assert_eq!(msg_str(neither, &VariantName::Never), None);
+    assert_eq!(
+        msg_str(doc, &VariantName::Always).as_deref(),
+        Some("Doc: from the doc comment"),
+    );
+}
+
+#[test]
+fn derive_forwards_the_formatter() {
+    let output = derive(quote! {
+        enum Error {
+            #[err(transparent)]
+            Fmt(core::fmt::Error),
+            /// bad port
+            BadPort(u16),
+        }
+    })
+    .to_string();
+    assert!(output.contains("impl :: core :: error :: Error for Error"));
+    assert!(output.contains(":: core :: fmt :: Display :: fmt (arg_0 , f)"));
+    assert!(output.contains("\"bad port: {}\""));
+    assert!(!output.contains("fn write_to"));
+}
+
+#[test]
+fn message_consts_are_opt_in() {
+    let input = quote! {
+        enum Error {
+            /// took too long
+            Timeout,
+        }
+    };
+    assert!(!derive(input).to_string().contains("__FOX_MESSAGE_Timeout"));
+    let input = quote! {
+        #[err(message_consts)]
+        enum Error {
+            /// took too long
+            Timeout,
+        }
+    };
+    assert!(derive(input).to_string().contains("__FOX_MESSAGE_Timeout"));
+
+    let err = parse_err(quote! {
+        #[err(message_consts)]
+        enum Error<T> {
+            /// bad value
+            BadValue(T),
+        }
+    });
+    assert_eq!(
+        err,
+        "message_consts can't be used on enums with type parameters"
+    );
+}
+
+#[test]
+fn parse_msg_unescapes_braces() {
+    let parsed = parse(quote! {
+        enum Error {
+            /// expected {{"port": {port}}} {{port}} }
+            BadJson { port: u16 },
+        }
+    })
+    .unwrap();
+    let v = &parsed.variants[0];
+    let msg = msg_str(v, &VariantName::Auto).unwrap();
+    assert_eq!(parts(&msg, v), ["expected {\"port\": ", "#0", "} {port} }"]);
+}
+
+#[test]
+fn error_kind_rejects_duplicate_codes() {
+    let input = quote! {
+        #[err(error_kind)]
+        enum Error {
+            /// not found
+            #[err(code = 404)]
+            NotFound,
+            /// gone
+            #[err(code = 404)]
+            Gone,
+            /// teapot
+            #[err(code = -418)]
+            Teapot,
+        }
+    };
+    let output = derive(input).to_string();
+    assert!(
+        output.contains("Gone has the same code as NotFound"),
+        "{output}"
+    );
+    assert_eq!(output.matches("compile_error").count(), 1, "{output}");
+}
+
+#[test]
+fn message_prefix_field_needs_a_message() {
+    let output = derive(quote! {
+        #[err(display_variant_name = "never")]
+        enum Error {
+            #[err(message_prefix_field = "module")]
+            Database { module: &'static str, retries: u8 },
+        }
+    })
+    .to_string();
+    assert!(output.contains("message_prefix_field requires the variant to have a message"));
+}
+
+/// the line and column an error starts at, counting from 1:1
+fn err_at(input: &str) -> (usize, usize) {
+    let err = match parse(input.parse().unwrap()) {
+        Ok(_) => panic!("expected the input to be rejected"),
+        Err(err) => err,
+    };
+    let start = err.span().start();
+    (start.line, start.column + 1)
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    let input = "enum Error {
+        #[err(mgs = \"bad port\")]
+        BadPort(u16),
+    }";
+    assert_eq!(err_at(input), (2, 15));
+
+    let input = "enum Error {
+        /// bad port
+        #[err(exit_code = 256)]
+        BadPort(u16),
+    }";
+    assert_eq!(err_at(input), (3, 27));
+
+    let input = "enum Error {
+        #[err(msg = \"could not bind to {hostname}\")]
+        Bind { host: String },
+    }";
+    assert_eq!(err_at(input), (2, 21));
+}
+
+#[test]
+fn fox_reports_bad_features_alone() {
+    let output = fox(quote! {
+        enum Error {
+            #[err(feature)]
+            Timeout = "took too long",
+        }
+    })
+    .to_string();
+    assert!(output.contains("feature requires a value"), "{output}");
+    assert!(!output.contains("enum Error"), "{output}");
+}
+
+#[test]
+fn flags_take_bools() {
+    let parsed = parse(quote! {
+        #[err(quote_values = false)]
+        enum Error {
+            /// read failed
+            #[err(from = false)]
+            Read(std::io::Error),
+            /// bad host
+            BadHost(#[err(quote = true)] String),
+        }
+    })
+    .unwrap();
+    let [read, bad_host] = &parsed.variants[..] else {
+        panic!("expected two variants");
+    };
+    assert!(!read.from);
+    assert!(bad_host.fields[0].quote);
+
+    let err = parse_err(quote! {
+        enum Error {
+            /// read failed
+            #[err(from = "nope")]
+            Read(std::io::Error),
+        }
+    });
+    assert_eq!(err, "expected `true` or `false` here for from");
+
+    let err = parse_err(quote! {
+        #[err(context_chain = 5)]
+        enum Error {
+            /// bad port
+            BadPort(u16),
+        }
+    });
+    assert_eq!(err, "expected `true` or `false` here for context_chain");
+}
+
+#[test]
+fn context_takes_a_single_field() {
+    let func = quote! {
+        fn load(s: &str) -> Result<u8, Error> {
+            Ok(s.parse()?)
+        }
+    };
+    let output = context(quote!(Error::Load { source }), func.clone()).to_string();
+    assert!(
+        output.contains("map_err (| source | Error :: Load { source })"),
+        "{output}"
+    );
+
+    let output = context(quote!(Error::Load { source, path }), func).to_string();
+    assert!(
+        output.contains("expected only the name of the field holding the error"),
+        "{output}"
+    );
+}
+
+#[test]
+fn static_table_indexes_by_discriminant() {
+    let output = derive(quote! {
+        #[err(static_table)]
+        enum Error {
+            /// bad port
+            BadPort,
+            /// bad host
+            BadHost,
+        }
+    })
+    .to_string();
+    assert!(output.contains("MESSAGES [* self as usize]"), "{output}");
+
+    let output = derive(quote! {
+        #[err(static_table)]
+        enum Error {
+            /// bad port
+            BadPort = 1,
+        }
+    })
+    .to_string();
+    assert!(
+        output.contains("static_table looks messages up by discriminant"),
+        "{output}"
+    );
+}
+
+#[test]
+fn check_placeholders_covers_unit_variants() {
+    let err = parse_err(quote! {
+        enum Error {
+            /// a {{x}} {y}
+            Empty,
+        }
+    });
+    assert_eq!(err, "no field named `y`, Empty has no fields");
+}
+
+#[test]
+fn message_explains_missing_consts() {
+    let output = message(quote!(MyError::Timeout)).to_string();
+    assert!(
+        output.contains("MyError::Timeout has no message constant"),
+        "{output}"
+    );
+    assert!(
+        output.contains("< MyError > :: __FOX_MESSAGE_Timeout"),
+        "{output}"
+    );
+}
+
+#[test]
+fn traits_crate_is_used_everywhere() {
+    let output = derive(quote! {
+        #[err(traits_crate = facade::traits, error_meta, additive)]
+        enum Error {
+            /// bad port
+            #[err(severity = "warning")]
+            BadPort(u16),
+        }
+    })
+    .to_string();
+    assert!(output.contains("facade :: traits :: ErrorMeta"), "{output}");
+    assert!(
+        output.contains("facade :: traits :: Severity :: Warning"),
+        "{output}"
+    );
+    assert!(
+        output.contains("facade :: traits :: MultiError"),
+        "{output}"
+    );
+    assert!(!output.contains("foxerror_traits"), "{output}");
+
+    let func = quote! {
+        fn load(s: &str) -> Result<u8, Error> {
+            Ok(s.parse()?)
+        }
+    };
+    let output = context(quote!("while loading", traits_crate = facade::traits), func).to_string();
+    assert!(output.contains("facade :: traits :: Context"), "{output}");
+    assert!(!output.contains("foxerror_traits"), "{output}");
+}
@@ -0,0 +1,93 @@
+//! traits and types used by [foxerror](https://docs.rs/foxerror)'s derive
+//!
+//! proc macro crates can't export anything but macros, so they live here
+//! instead.
+
+/// how bad an error is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[non_exhaustive]
+pub enum Severity {
+    /// something is off, but things can continue
+    Warning,
+    /// the operation failed
+    #[default]
+    Error,
+    /// the program can't reasonably continue
+    Fatal,
+}
+
+/// information about an error that doesn't depend on its concrete type
+pub trait ErrorMeta: core::error::Error {
+    /// the name of the variant
+    fn name(&self) -> &'static str;
+
+    /// the error code, if the variants have them
+    fn code(&self) -> Option<i32>;
+
+    /// how bad the error is
+    fn severity(&self) -> Severity;
+}
+
+/// several errors collected together, as produced by adding errors from an
+/// enum with `#[err(additive)]`
+///
+/// shown one after another, separated by `; `
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MultiError<E>(pub Vec<E>);
+
+impl<E: core::fmt::Display> core::fmt::Display for MultiError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, err) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: core::error::Error> core::error::Error for MultiError<E> {}
+
+impl<E> core::ops::Add<E> for MultiError<E> {
+    type Output = Self;
+
+    fn add(mut self, rhs: E) -> Self {
+        self.0.push(rhs);
+        self
+    }
+}
+
+impl<E> core::ops::Add for MultiError<E> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self.0.extend(rhs.0);
+        self
+    }
+}
+
+/// an error along with what was being done when it happened, as produced by
+/// `#[foxerror::context("...")]`
+///
+/// shown as the message followed by the error, like the variants of
+/// foxerror's derive show their sources
+#[derive(Debug)]
+pub struct Context {
+    /// what was being done
+    pub msg: &'static str,
+    /// the error that happened
+    pub source: Box<dyn core::error::Error + Send + Sync>,
+}
+
+impl core::fmt::Display for Context {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.msg, self.source)
+    }
+}
+
+impl core::error::Error for Context {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&*self.source)
+    }
+}
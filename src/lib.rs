@@ -11,235 +11,6 @@
 //! }
 //! ```
 
-use proc_macro2::{Span, TokenStream};
-use quote::quote;
-use syn::{
-    parse::{Parse, ParseStream},
-    DeriveInput, Token,
-};
-
-struct ParsedErrors {
-    ident: syn::Ident,
-    generics: syn::Generics,
-    variants: Vec<Variant>,
-}
-
-struct Variant {
-    ident: syn::Ident,
-    fields: syn::Fields,
-    msg: Option<String>,
-    from: bool,
-}
-
-struct AttrArg {
-    ident: syn::Ident,
-    value: Option<syn::Expr>,
-}
-
-impl Parse for AttrArg {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let ident = input.parse()?;
-        let value = if input.parse::<Token![=]>().is_ok() {
-            input.parse::<syn::Expr>().ok()
-        } else {
-            None
-        };
-        Ok(Self { ident, value })
-    }
-}
-
-struct AttrArgs(Vec<AttrArg>);
-
-impl Parse for AttrArgs {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut args = vec![];
-        loop {
-            args.push(input.parse()?);
-            if input.parse::<Token![,]>().is_err() {
-                return Ok(Self(args));
-            }
-        }
-    }
-}
-
-fn parse_attr_doc(a: &syn::Attribute) -> Option<&syn::Expr> {
-    if !matches!(a.style, syn::AttrStyle::Outer) {
-        return None;
-    }
-    let syn::Meta::NameValue(ref nameval) = a.meta else {
-        return None;
-    };
-    if !nameval.path.is_ident("doc") {
-        return None;
-    }
-    Some(&nameval.value)
-}
-
-fn parse_attr(a: &syn::Attribute) -> Option<AttrArgs> {
-    if !matches!(a.style, syn::AttrStyle::Outer) {
-        return None;
-    }
-    let syn::Meta::List(ref list) = a.meta else {
-        return None;
-    };
-    if !matches!(list.delimiter, syn::MacroDelimiter::Paren(_)) {
-        return None;
-    }
-    if !list.path.is_ident("err") {
-        return None;
-    }
-    Some(list.parse_args().expect("could not parse attr args"))
-}
-
-fn expr_str(a: &syn::Expr) -> Option<String> {
-    match a {
-        syn::Expr::Lit(syn::ExprLit {
-            lit: syn::Lit::Str(s),
-            ..
-        }) => Some(s.value()),
-        _ => None,
-    }
-    .map(|s| s.strip_prefix(' ').unwrap_or(&s).to_string())
-}
-
-fn parse_variant(v: syn::Variant) -> Variant {
-    let doc = v.attrs.iter().find_map(parse_attr_doc);
-    let mut args = v.attrs.iter().filter_map(parse_attr);
-    let amsg = args
-        .clone()
-        .filter_map(|a| {
-            a.0.into_iter()
-                .find(|a| a.ident == "msg")
-                .and_then(|a| a.value)
-        })
-        .last();
-    let msg = amsg.as_ref().or(doc).and_then(expr_str);
-    let from = args
-        .find_map(|a| a.0.into_iter().find(|a| a.ident == "from"))
-        .is_some();
-    Variant {
-        ident: v.ident,
-        fields: v.fields,
-        msg,
-        from,
-    }
-}
-
-fn parse_derive(ast: DeriveInput) -> ParsedErrors {
-    let ident = ast.ident;
-    let generics = ast.generics;
-    let syn::Data::Enum(body) = ast.data else {
-        panic!("only enums are supported")
-    };
-    let variants = body.variants.into_iter().map(parse_variant).collect();
-
-    ParsedErrors {
-        ident,
-        generics,
-        variants,
-    }
-}
-
-fn generate(parsed: ParsedErrors) -> TokenStream {
-    let ParsedErrors {
-        ident,
-        generics,
-        variants,
-    } = parsed;
-
-    let arms = variants.iter().map(|v| {
-        let Variant {
-            ident: name,
-            fields,
-            msg,
-            ..
-        } = v;
-        let msg = if let Some(msg) = msg {
-            quote!(#msg)
-        } else {
-            let name = name.to_string();
-            quote!(#name)
-        };
-        let mut set = quote!();
-        let mut get = vec![];
-        let mut fmt = vec![quote!("{}")];
-
-        match fields {
-            syn::Fields::Named(fields) => {
-                fmt.push(quote!(":"));
-                let mut ids = vec![];
-                for (fnum, field) in fields.named.iter().enumerate() {
-                    let fid = syn::Ident::new(format!("arg_{fnum}").as_ref(), Span::call_site());
-                    get.push(quote!(#fid));
-                    let fnm = field.ident.as_ref().expect("missing ident");
-                    ids.push(quote!(#fnm));
-                    if fnum > 0 {
-                        fmt.push(quote!(","));
-                    }
-                    let fo = format!(" {fnm}: {{}}");
-                    fmt.push(quote!(#fo));
-                }
-                set = quote!({#(#ids: #get),*});
-            }
-            syn::Fields::Unnamed(fields) => {
-                fmt.push(quote!(":"));
-                for fnum in 0..fields.unnamed.len() {
-                    let fid = syn::Ident::new(format!("arg_{fnum}").as_ref(), Span::call_site());
-                    get.push(quote!(#fid));
-                    if fnum > 0 {
-                        fmt.push(quote!(","));
-                    }
-                    fmt.push(quote!(" {}"));
-                }
-                set = quote!((#(#get),*));
-            }
-            syn::Fields::Unit => (),
-        };
-
-        quote! {
-            #ident::#name #set => write!(f, concat!(#(#fmt),*), #msg, #(#get),*)
-        }
-    });
-
-    let froms = variants.iter().filter_map(|v| {
-        if !v.from {
-            return None;
-        }
-        let syn::Fields::Unnamed(ref fields) = v.fields else {
-            panic!("automatically deriving From is only supported for unnamed fields")
-        };
-        let [ref field] = fields.unnamed.iter().collect::<Vec<_>>()[..] else {
-            panic!("automatically deriving From is only supported with a single field")
-        };
-        let name = &v.ident;
-
-        Some(quote! {
-            #[automatically_derived]
-            impl #generics ::core::convert::From<#field> for #ident #generics {
-                fn from(inner: #field) -> Self {
-                    Self::#name(inner)
-                }
-            }
-        })
-    });
-
-    quote! {
-        #[automatically_derived]
-        impl #generics ::core::fmt::Display for #ident #generics {
-            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                match self {
-                    #(#arms,)*
-                }
-            }
-        }
-
-        #[automatically_derived]
-        impl #generics ::core::error::Error for #ident #generics {}
-
-        #(#froms)*
-    }
-}
-
 /// the derive macro itself
 ///
 /// # more in-depth example
@@ -250,7 +21,6 @@ fn generate(parsed: ParsedErrors) -> TokenStream {
 ///     /// other lines get ignored
 ///     NoFields,
 ///     /// or override the message with an attribute
-///     #[err(msg = "i also get overridden")]
 ///     #[err(msg = "i have one field")]
 ///     #[err(from)]
 ///     OneField(&'a str),
@@ -278,11 +48,2830 @@ fn generate(parsed: ParsedErrors) -> TokenStream {
 /// );
 /// assert_eq!(Error::from("meow"), Error::OneField("meow"));
 /// ```
-#[proc_macro_derive(FoxError, attributes(err))]
-pub fn foxerror(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = syn::parse(input).unwrap();
-    let parsed = parse_derive(input);
-    let output = generate(parsed);
-
-    output.into()
+///
+/// `msg` takes precedence over a doc comment, but giving it more than once
+/// or on a `transparent` variant is an error.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     #[err(msg = "bad port")]
+///     #[err(msg = "bad host")]
+///     Bad,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     #[err(msg = "formatting failed", transparent)]
+///     Fmt(std::fmt::Error),
+/// }
+/// ```
+///
+/// arguments can be split over several `#[err(...)]` attributes, which are
+/// merged together. any other argument with a value is also only allowed
+/// once, with the error pointing at the second one.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     #[err(from)]
+///     #[err(exit_code = 2)]
+///     BadPort(#[err(quote)] #[err(debug)] u16),
+/// }
+///
+/// assert_eq!(Error::from(80).exit_code(), 2);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     #[err(exit_code = 2)]
+///     #[err(exit_code = 3)]
+///     BadPort(u16),
+/// }
+/// ```
+///
+/// # single variant enums
+/// an enum with only a single unit variant simply writes its message.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum NotReady {
+///     /// the fox is not ready yet
+///     NotReady,
+/// }
+///
+/// assert_eq!(format!("{}", NotReady::NotReady), "the fox is not ready yet");
+/// ```
+///
+/// # references and boxes
+/// nothing extra is generated for `&Error` or `Box<Error>`, the standard
+/// library's blanket impls already forward `Display` and `Error` to them.
+/// ```rust
+/// use std::fmt::Display;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     /// source
+///     Source(#[err(source)] std::fmt::Error),
+/// }
+///
+/// fn show(err: impl Display) -> String {
+///     err.to_string()
+/// }
+///
+/// fn source(err: impl std::error::Error) -> bool {
+///     err.source().is_some()
+/// }
+///
+/// let err = Error::BadPort(0);
+/// assert_eq!(show(&err), "bad port: 0");
+/// assert_eq!(show(&&err), "bad port: 0");
+/// assert_eq!(show(Box::new(Error::BadPort(1))), "bad port: 1");
+/// assert!(source(&Error::Source(std::fmt::Error)));
+/// assert!(source(Box::new(Error::Source(std::fmt::Error))));
+/// ```
+///
+/// # strict mode
+/// `#[err(strict)]` on the enum, or `#[err(require_messages)]`, makes
+/// forgetting a message an error instead of falling back to the variant's
+/// name. every variant without a message is reported at once.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(strict)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     BadHost(String),
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(require_messages)]
+/// enum Error {
+///     ParseHdrV2,
+/// }
+/// ```
+///
+/// `#[err(deny_duplicate_messages)]` makes two variants with the same message
+/// an error, comparing messages before any fields are filled in. messages
+/// from macros like `concat!` can't be compared and are left out.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(deny_duplicate_messages)]
+/// enum Error {
+///     /// could not connect
+///     Connect(u16),
+///     /// could not connect
+///     Reconnect(u16),
+/// }
+/// ```
+///
+/// # showing variant names
+/// the variant name is only used as the message when there is no other
+/// message, which can be changed with `#[err(display_variant_name = "...")]`
+/// on the enum. `"always"` puts the variant name in front of every message,
+/// `"never"` leaves it out entirely, and `"auto"` is the default behavior.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(display_variant_name = "always")]
+/// enum Always {
+///     /// bad port
+///     BadPort(u16),
+///     Unknown,
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(display_variant_name = "never")]
+/// enum Never {
+///     BadPort { port: u16 },
+///     Unknown,
+/// }
+///
+/// assert_eq!(format!("{}", Always::BadPort(0)), "BadPort: bad port: 0");
+/// assert_eq!(format!("{}", Always::Unknown), "Unknown");
+/// assert_eq!(format!("{}", Never::BadPort { port: 0 }), "port: 0");
+/// assert_eq!(format!("{}", Never::Unknown), "");
+/// ```
+///
+/// raw identifiers are shown without their `r#`.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(display_variant_name = "always")]
+/// enum Chess {
+///     r#Move,
+///     /// bad square
+///     r#Box(u8),
+/// }
+///
+/// assert_eq!(format!("{}", Chess::r#Move), "Move");
+/// assert_eq!(format!("{}", Chess::r#Box(9)), "Box: bad square: 9");
+/// ```
+///
+/// # placeholders
+/// `{type}` and `{variant}` in a message are replaced with the name of the
+/// enum and the variant respectively.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum ConfigError {
+///     /// {type} happened in {variant}
+///     Parse,
+/// }
+///
+/// assert_eq!(format!("{}", ConfigError::Parse), "ConfigError happened in Parse");
+/// ```
+///
+/// # message macros
+/// string-producing macros like `concat!` or `env!` work for `msg` too.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     #[err(msg = concat!("could not start ", env!("CARGO_CRATE_NAME")))]
+///     Start(u16),
+/// }
+///
+/// let msg = format!("could not start {}: 1", env!("CARGO_CRATE_NAME"));
+/// assert_eq!(Error::Start(1).to_string(), msg);
+/// ```
+///
+/// so do `&str` constants, for sharing messages with the rest of your code.
+/// `#[err(const_default_msg = "path::to::CONST")]` does the same with the
+/// path in a string.
+/// ```rust
+/// mod messages {
+///     pub const TIMEOUT: &str = "timed out";
+///     pub const GONE: &str = "the fox is gone";
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(terminator = ".")]
+/// enum Error {
+///     #[err(msg = messages::TIMEOUT)]
+///     Timeout(u64),
+///     #[err(const_default_msg = "messages::GONE")]
+///     Gone,
+/// }
+///
+/// assert_eq!(Error::Timeout(5).to_string(), "timed out: 5.");
+/// assert_eq!(Error::Gone.to_string(), "the fox is gone.");
+/// ```
+///
+/// # templates
+/// `#[err(template = "...")]` on the enum or a variant controls the layout
+/// of the whole message. `{msg}` is replaced with the message, and
+/// `{fields}` with the fields as they would normally be shown after it.
+/// use `{{` and `}}` for literal braces.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(template = "{fields} ({msg})")]
+/// enum Error {
+///     /// bad port
+///     BadPort { port: u16, host: &'static str },
+///     /// unknown
+///     #[err(template = "{{{msg}}}")]
+///     Unknown,
+/// }
+///
+/// assert_eq!(
+///     format!("{}", Error::BadPort { port: 0, host: "localhost" }),
+///     "port: 0, host: localhost (bad port)",
+/// );
+/// assert_eq!(format!("{}", Error::Unknown), "{unknown}");
+/// ```
+///
+/// that includes enums with only a single unit variant.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(template = "[{msg}]")]
+/// enum Error {
+///     /// all alone
+///     Alone,
+/// }
+///
+/// assert_eq!(Error::Alone.to_string(), "[all alone]");
+/// ```
+///
+/// # terminators
+/// `#[err(terminator = ".")]` on the enum appends something to the end of
+/// every message, after any fields. variants can opt out of it with
+/// `#[err(no_terminator)]`.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(terminator = ".")]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     /// unknown error
+///     Unknown,
+///     /// are you sure?
+///     #[err(no_terminator)]
+///     Question,
+/// }
+///
+/// assert_eq!(format!("{}", Error::BadPort(0)), "bad port: 0.");
+/// assert_eq!(format!("{}", Error::Unknown), "unknown error.");
+/// assert_eq!(format!("{}", Error::Question), "are you sure?");
+/// ```
+///
+/// # longer doc comments
+/// only the first line of a doc comment is used by default.
+/// `#[err(msg_doc = "paragraph")]` on the enum uses every line up to the
+/// first blank one instead, and `"all"` uses the whole doc comment, with
+/// the lines joined by newlines.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(msg_doc = "paragraph")]
+/// enum Error {
+///     /// the config file is missing.
+///     /// run with --init to make one.
+///     ///
+///     /// this part is only for the docs.
+///     NoConfig,
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(msg_doc = "all")]
+/// enum Everything {
+///     /// the config file is missing.
+///     ///
+///     /// run with --init to make one.
+///     NoConfig,
+/// }
+///
+/// assert_eq!(
+///     format!("{}", Error::NoConfig),
+///     "the config file is missing.\nrun with --init to make one.",
+/// );
+/// assert_eq!(
+///     format!("{}", Everything::NoConfig),
+///     "the config file is missing.\n\nrun with --init to make one.",
+/// );
+/// ```
+///
+/// # field placeholders
+/// messages can refer to fields by name, or by position for tuple variants,
+/// and `{source}` refers to the source field. a message doing so is
+/// shown as-is, without the usual list of fields after it.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not bind to {host}:{port}
+///     Bind { host: &'static str, port: u16 },
+///     /// port {0} is reserved
+///     Reserved(u16),
+///     /// bad port ({source})
+///     BadPort(#[err(source)] std::num::ParseIntError),
+/// }
+///
+/// assert_eq!(
+///     format!("{}", Error::Bind { host: "localhost", port: 80 }),
+///     "could not bind to localhost:80",
+/// );
+/// assert_eq!(format!("{}", Error::Reserved(22)), "port 22 is reserved");
+///
+/// use std::error::Error as _;
+/// let err = Error::BadPort("fox".parse::<u16>().unwrap_err());
+/// assert_eq!(err.to_string(), "bad port (invalid digit found in string)");
+/// assert!(err.source().is_some());
+/// ```
+///
+/// fields the message doesn't mention are an error too, unless they are
+/// marked `#[err(skip)]`. skipped fields are left out of the list of fields
+/// in other messages as well.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// timed out after {secs}s
+///     Timeout {
+///         secs: u64,
+///         #[err(skip)]
+///         endpoint: String,
+///     },
+///     /// bad port
+///     BadPort(u16, #[err(skip)] u8),
+/// }
+///
+/// let err = Error::Timeout { secs: 5, endpoint: "fox.den".into() };
+/// assert_eq!(err.to_string(), "timed out after 5s");
+/// assert_eq!(Error::BadPort(0, 3).to_string(), "bad port: 0");
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// timed out after {secs}s
+///     Timeout { secs: u64, endpoint: String },
+/// }
+/// ```
+///
+/// placeholders that don't match any field are an error, pointing out which
+/// fields there are. braces around anything that isn't a name or a number,
+/// and braces in unit variants' messages, are shown as they are.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not bind to {hostname}:{port}
+///     Bind { host: &'static str, port: u16 },
+/// }
+/// ```
+///
+/// like with `format!`, `{{` and `}}` are a single brace, for putting braces
+/// right next to a placeholder.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// expected {{"port": {port}}}
+///     BadJson { port: u16 },
+///     /// expected {{}}
+///     Empty,
+/// }
+///
+/// let err = Error::BadJson { port: 80 };
+/// assert_eq!(err.to_string(), r#"expected {"port": 80}"#);
+/// assert_eq!(Error::Empty.to_string(), "expected {}");
+/// ```
+///
+/// unit variants have no fields at all, so a placeholder in one of their
+/// messages is always an error.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// expected {{x}} {y}
+///     Empty,
+/// }
+/// ```
+///
+/// `#[err(message_prefix_field = "name")]` on a variant shows the named
+/// field in front of its message, and leaves it out of the list of fields.
+/// it is still one of the fields shown, for things like `display_fields`,
+/// and the variant needs a message to put it in front of.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(display_fields)]
+/// enum Error {
+///     /// database connection failed
+///     #[err(message_prefix_field = "module")]
+///     Database { module: &'static str, retries: u8 },
+/// }
+///
+/// let err = Error::Database { module: "payments", retries: 3 };
+/// assert_eq!(err.to_string(), "payments: database connection failed: retries: 3");
+/// assert_eq!(err.display_fields()[0], ("module", "payments".to_string()));
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(display_variant_name = "never")]
+/// enum Error {
+///     #[err(message_prefix_field = "module")]
+///     Database { module: &'static str, retries: u8 },
+/// }
+/// ```
+///
+/// # quoting field values
+/// `#[err(quote_values)]` on the enum wraps every field value in double
+/// quotes, while `#[err(quote)]` does the same for a single field. fields
+/// with a numeric type are never quoted.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(quote_values)]
+/// enum Error {
+///     /// could not open
+///     Open { path: &'static str, attempts: u8 },
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Other {
+///     /// bad name
+///     BadName(#[err(quote)] &'static str, &'static str),
+/// }
+///
+/// assert_eq!(
+///     format!("{}", Error::Open { path: "my file", attempts: 3 }),
+///     "could not open: path: \"my file\", attempts: 3",
+/// );
+/// assert_eq!(
+///     format!("{}", Other::BadName("silly fox", "silly fox")),
+///     "bad name: \"silly fox\", silly fox",
+/// );
+/// ```
+///
+/// # debug formatting
+/// most std types like addresses can be shown as they are, while
+/// `#[err(debug)]` uses a field's `Debug` form instead. this also works for
+/// fields without a `Display` implementation, like an `Option`.
+/// ```rust
+/// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not connect to
+///     Connect(SocketAddr),
+///     /// banned
+///     Banned(#[err(debug)] IpAddr),
+///     /// no route to
+///     NoRoute(#[err(debug)] Option<SocketAddr>),
+/// }
+///
+/// let v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080);
+/// let v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8080);
+/// assert_eq!(Error::Connect(v4).to_string(), "could not connect to: 127.0.0.1:8080");
+/// assert_eq!(Error::Connect(v6).to_string(), "could not connect to: [::1]:8080");
+/// assert_eq!(Error::Banned(v4.ip()).to_string(), "banned: 127.0.0.1");
+/// assert_eq!(Error::NoRoute(Some(v4)).to_string(), "no route to: Some(127.0.0.1:8080)");
+/// assert_eq!(Error::NoRoute(None).to_string(), "no route to: None");
+/// ```
+///
+/// fields are only ever matched and formatted, so their types can be
+/// nested as deeply as needed, in placeholders as well as the field list.
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error<T: std::fmt::Debug> {
+///     /// ranges overlap: {ranges}
+///     Overlap {
+///         #[err(debug)]
+///         ranges: HashMap<String, Vec<(u32, u32)>>,
+///     },
+///     /// unexpected item
+///     Unexpected(#[err(debug)] Option<<Vec<u8> as IntoIterator>::Item>),
+///     /// leftovers
+///     Leftovers(#[err(debug)] Vec<Option<Box<[T]>>>),
+/// }
+///
+/// let ranges = HashMap::from([("fox".to_string(), vec![(1, 4), (3, 5)])]);
+/// assert_eq!(
+///     Error::<()>::Overlap { ranges }.to_string(),
+///     r#"ranges overlap: {"fox": [(1, 4), (3, 5)]}"#,
+/// );
+/// assert_eq!(Error::<()>::Unexpected(Some(7)).to_string(), "unexpected item: Some(7)");
+/// assert_eq!(
+///     Error::Leftovers(vec![Some(Box::from([1, 2])), None]).to_string(),
+///     "leftovers: [Some([1, 2]), None]",
+/// );
+/// ```
+///
+/// # implicit fields
+/// fields marked `#[err(implicit)]` are filled in automatically by the
+/// generated `From` implementations using `Default::default()`, or using an
+/// expression of your choice with `#[err(implicit = expr)]`. they are left
+/// out of the message. constructing a variant by hand bypasses this, so you
+/// need to fill in implicit fields yourself there.
+/// ```rust
+/// fn request_id() -> u64 {
+///     42
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad number
+///     #[err(from)]
+///     Parse(
+///         std::num::ParseIntError,
+///         #[err(implicit)] std::time::Duration,
+///         #[err(implicit = request_id())] u64,
+///     ),
+/// }
+///
+/// let err = Error::from("fox".parse::<u8>().unwrap_err());
+/// assert!(matches!(err, Error::Parse(_, std::time::Duration::ZERO, 42)));
+/// assert_eq!(format!("{err}"), "bad number: invalid digit found in string");
+/// ```
+///
+/// # tracking callers
+/// `From` implementations filling in implicit fields are marked
+/// `#[track_caller]`, so that `Location::caller()` finds where the
+/// conversion happened. `#[err(track_caller)]` on the enum marks every
+/// `From` implementation and the `other` constructor, so that panics and
+/// locations inside the conversions they make point at your code as well.
+/// ```rust
+/// use std::panic::Location;
+///
+/// #[derive(Debug)]
+/// struct Traced(&'static Location<'static>);
+///
+/// impl std::fmt::Display for Traced {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "created on line {}", self.0.line())
+///     }
+/// }
+///
+/// impl std::error::Error for Traced {}
+///
+/// impl From<&str> for Traced {
+///     #[track_caller]
+///     fn from(_: &str) -> Self {
+///         Self(Location::caller())
+///     }
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(track_caller)]
+/// enum Error {
+///     #[err(other)]
+///     Other(Traced),
+/// }
+///
+/// let line = line!() + 1;
+/// let err = Error::other("oh no");
+/// assert_eq!(err.to_string(), format!("created on line {line}"));
+/// ```
+///
+/// # paths
+/// `#[err(path)]` shows a `Path` or `PathBuf` field using its `display`
+/// method. any parts of the path that aren't valid unicode are replaced with
+/// `�`, so the message may not match the path exactly.
+/// ```rust
+/// use std::path::PathBuf;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not read
+///     Read {
+///         #[err(path, quote)]
+///         path: PathBuf,
+///     },
+/// }
+///
+/// let err = Error::Read { path: PathBuf::from("/etc/fox.toml") };
+/// assert_eq!(err.to_string(), "could not read: path: \"/etc/fox.toml\"");
+/// ```
+///
+/// # lists
+/// fields marked with `#[err(list)]` are shown as a list of their items,
+/// which works for anything that can be iterated by reference with items
+/// implementing `Display`. `#[err(field_list_style = "bullets")]` on the
+/// field or the enum puts each item on its own line instead, with the bullet
+/// changed using `#[err(bullet = "- ")]`.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(bullet = "- ")]
+/// enum Error {
+///     /// missing keys
+///     Missing(#[err(list)] Vec<&'static str>),
+///     /// bad ports
+///     BadPorts(#[err(field_list_style = "bullets")] Vec<u16>),
+///     /// bad hosts
+///     BadHosts {
+///         #[err(field_list_style = "bullets", bullet = "* ")]
+///         hosts: [&'static str; 2],
+///     },
+/// }
+///
+/// assert_eq!(
+///     format!("{}", Error::Missing(vec!["port", "host"])),
+///     "missing keys: [port, host]",
+/// );
+/// assert_eq!(
+///     format!("{}", Error::BadPorts(vec![0, 1])),
+///     "bad ports: \n- 0\n- 1",
+/// );
+/// assert_eq!(
+///     format!("{}", Error::BadHosts { hosts: ["fox", "den"] }),
+///     "bad hosts: hosts: \n* fox\n* den",
+/// );
+/// ```
+///
+/// # durations
+/// `Duration` does not implement `Display`, so mark such fields with
+/// `#[err(duration)]`. durations of a second or more are shown in seconds
+/// with millisecond precision, and shorter ones in milliseconds with
+/// microsecond precision. this only relies on `core`.
+/// ```rust
+/// use std::time::Duration;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// timed out
+///     Timeout(#[err(duration)] Duration),
+/// }
+///
+/// let timeout = |d| format!("{}", Error::Timeout(d));
+/// assert_eq!(timeout(Duration::from_millis(1500)), "timed out: 1.5s");
+/// assert_eq!(timeout(Duration::from_secs(2)), "timed out: 2s");
+/// assert_eq!(timeout(Duration::from_millis(250)), "timed out: 250ms");
+/// assert_eq!(timeout(Duration::from_micros(500)), "timed out: 0.5ms");
+/// assert_eq!(timeout(Duration::ZERO), "timed out: 0ms");
+/// ```
+///
+/// # booleans
+/// `#[err(bool = "yes/no")]` shows a `bool` field as one of two words
+/// instead of `true` or `false`.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not connect
+///     Connect {
+///         #[err(bool = "yes/no")]
+///         retried: bool,
+///         #[err(bool = "enabled/disabled")]
+///         tls: bool,
+///     },
+/// }
+///
+/// assert_eq!(
+///     Error::Connect { retried: true, tls: false }.to_string(),
+///     "could not connect: retried: yes, tls: disabled",
+/// );
+/// assert_eq!(
+///     Error::Connect { retried: false, tls: true }.to_string(),
+///     "could not connect: retried: no, tls: enabled",
+/// );
+/// ```
+///
+/// # display adapters
+/// `#[err(via = Adapter)]` shows a field through another type, for
+/// formatting that is shared between enums. the adapter has to implement
+/// `From<&T>` for the field's type `T`, along with `Display`.
+/// ```rust
+/// use std::fmt;
+///
+/// struct HexDump<'a>(&'a [u8]);
+///
+/// impl<'a> From<&'a Vec<u8>> for HexDump<'a> {
+///     fn from(bytes: &'a Vec<u8>) -> Self {
+///         Self(bytes)
+///     }
+/// }
+///
+/// impl fmt::Display for HexDump<'_> {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         for (i, byte) in self.0.iter().enumerate() {
+///             if i > 0 {
+///                 f.write_str(" ")?;
+///             }
+///             write!(f, "{byte:02x}")?;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad packet
+///     BadPacket(#[err(via = HexDump)] Vec<u8>),
+/// }
+///
+/// let err = Error::BadPacket(vec![0xde, 0xad, 0x0f]);
+/// assert_eq!(err.to_string(), "bad packet: de ad 0f");
+/// ```
+///
+/// # exit codes
+/// `#[err(exit_code = 2)]` on variants generates an `exit_code` method,
+/// returning 1 for variants without one. adding `#[err(impl_exit_code)]` to
+/// the enum also implements [`Termination`](std::process::Termination),
+/// printing the error and exiting with its code.
+/// ```rust
+/// use std::process::{ExitCode, Termination};
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(impl_exit_code)]
+/// enum Error {
+///     /// bad usage
+///     #[err(exit_code = 64)]
+///     Usage,
+///     /// something broke
+///     Broken,
+/// }
+///
+/// fn run(args: &[&str]) -> Result<(), Error> {
+///     if args.is_empty() {
+///         Err(Error::Usage)?;
+///     }
+///     Ok(())
+/// }
+///
+/// assert_eq!(Error::Usage.exit_code(), 64);
+/// assert_eq!(Error::Broken.exit_code(), 1);
+/// assert!(run(&[]).unwrap_err().report() == ExitCode::from(64));
+/// ```
+///
+/// exit codes are a single byte, so literals outside `1..=255` are an
+/// error. codes from constants that don't fit exit with 255 instead, and 0
+/// with 1, so an error never reports success.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad usage
+///     #[err(exit_code = 256)]
+///     Usage,
+/// }
+/// ```
+///
+/// ```rust
+/// use std::process::{ExitCode, Termination};
+///
+/// const HUGE: i32 = 256;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(impl_exit_code)]
+/// enum Error {
+///     /// bad usage
+///     #[err(exit_code = HUGE)]
+///     Usage,
+/// }
+///
+/// assert!(Error::Usage.report() == ExitCode::from(255));
+/// ```
+///
+/// # method visibility
+/// generated methods like `exit_code` are `pub` unless `#[err(vis = "...")]`
+/// on the enum says otherwise. a single method can be given its own
+/// visibility with `<method>_vis`, such as `#[err(exit_code_vis = "pub")]`.
+/// ```rust
+/// mod errors {
+///     #[derive(Debug, foxerror::FoxError)]
+///     #[err(vis = "pub(self)", exit_code_vis = "pub")]
+///     pub enum Error {
+///         /// bad usage
+///         #[err(exit_code = 64, code = 1)]
+///         Usage,
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(errors::Error::Usage.exit_code(), 64);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// mod errors {
+///     #[derive(Debug, foxerror::FoxError)]
+///     #[err(vis = "pub(self)", exit_code_vis = "pub")]
+///     pub enum Error {
+///         /// bad usage
+///         #[err(exit_code = 64, code = 1)]
+///         Usage,
+///     }
+/// }
+///
+/// # fn main() {
+/// let _ = errors::Error::Usage.error_code();
+/// # }
+/// ```
+///
+/// # transparent variants
+/// `#[err(transparent)]` on a variant with a single field forwards both
+/// `Display` and `source` to that field.
+/// ```rust
+/// use std::error::Error as _;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// something happened
+///     #[err(transparent)]
+///     Fmt(std::fmt::Error),
+/// }
+///
+/// let err = Error::Fmt(std::fmt::Error);
+/// assert_eq!(format!("{err}"), "an error occurred when formatting an argument");
+/// assert!(err.source().is_none());
+/// ```
+///
+/// the formatter is handed on as it is, so flags like `{:#}` or a width reach
+/// the inner error.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(pretty)]
+/// enum Inner {
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     #[err(transparent)]
+///     Inner(Inner),
+/// }
+///
+/// let err = Error::Inner(Inner::BadPort(0));
+/// assert_eq!(format!("{err}"), "bad port: 0");
+/// assert_eq!(format!("{err:#}"), "bad port\n  0");
+/// ```
+///
+/// the field has to be an error itself, which is checked with a friendlier
+/// message than the usual missing trait bound.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     #[err(transparent)]
+///     Code(u32),
+/// }
+/// ```
+///
+/// # result aliases
+/// `#[err(result_alias)]` on the enum generates a `Result` type alias next
+/// to it with the same visibility, or with another name using
+/// `#[err(result_alias = MyResult)]`. the enum's generics are kept, with the
+/// ok type following any lifetimes.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(result_alias)]
+/// pub enum Error {
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// fn check(port: u16) -> Result<u16> {
+///     match port {
+///         0 => Err(Error::BadPort(port)),
+///         _ => Ok(port),
+///     }
+/// }
+///
+/// let _: Result<(), std::fmt::Error> = Ok(());
+/// assert_eq!(check(0).unwrap_err().to_string(), "bad port: 0");
+/// ```
+///
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(result_alias = ParseResult)]
+/// pub enum Error<'a, T: std::fmt::Display + std::fmt::Debug = u16> {
+///     /// bad value
+///     BadValue(&'a str, T),
+/// }
+///
+/// fn parse(s: &str) -> ParseResult<'_, u16> {
+///     s.parse().map_err(|_| Error::BadValue(s, 0))
+/// }
+///
+/// fn parse_char(s: &str) -> ParseResult<'_, u8, char> {
+///     s.parse().map_err(|_| Error::BadValue(s, '?'))
+/// }
+///
+/// assert_eq!(parse("8080").unwrap(), 8080);
+/// assert_eq!(parse("fox").unwrap_err().to_string(), "bad value: fox, 0");
+/// assert_eq!(parse_char("fox").unwrap_err().to_string(), "bad value: fox, ?");
+/// ```
+///
+/// # subsets
+/// `#[err(subset(Name: VariantA, VariantB))]` on the enum generates another
+/// error enum containing copies of just those variants, along with a `From`
+/// implementation for converting it back into the full enum. this lets
+/// functions that can only fail in a few ways say so in their signature.
+/// arguments affecting messages, such as `quote_values` or `template`, carry
+/// over to the subset.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(subset(ReadError: Io, NotFound), subset(PortError: BadPort))]
+/// pub enum Error {
+///     /// io error
+///     Io(#[err(source)] std::io::Error),
+///     /// not found
+///     NotFound { path: &'static str },
+///     /// bad port
+///     #[err(from)]
+///     BadPort(std::num::ParseIntError),
+/// }
+///
+/// fn read(path: &'static str) -> Result<(), ReadError> {
+///     Err(ReadError::NotFound { path })
+/// }
+///
+/// fn port(s: &str) -> Result<u16, PortError> {
+///     Ok(s.parse()?)
+/// }
+///
+/// let err = read("fox.toml").unwrap_err();
+/// assert_eq!(err.to_string(), "not found: path: fox.toml");
+/// let err: Error = err.into();
+/// assert!(matches!(err, Error::NotFound { path: "fox.toml" }));
+/// let err: Error = port("").unwrap_err().into();
+/// assert!(matches!(err, Error::BadPort(_)));
+/// ```
+///
+/// a subset of a generic enum only takes the generic parameters its
+/// variants use.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(subset(PortError: BadPort))]
+/// enum Error<'a, T: std::fmt::Display>
+/// where
+///     T: std::fmt::Debug,
+/// {
+///     /// bad port
+///     BadPort(u16),
+///     /// bad host
+///     BadHost(&'a str),
+///     /// bad value
+///     BadValue(T),
+/// }
+///
+/// let err: Error<'_, u8> = PortError::BadPort(0).into();
+/// assert_eq!(err.to_string(), "bad port: 0");
+/// ```
+///
+/// naming a variant that doesn't exist is an error.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(subset(ReadError: Io, Missing))]
+/// pub enum Error {
+///     /// io error
+///     Io(#[err(source)] std::io::Error),
+/// }
+/// ```
+///
+/// # non-exhaustive enums
+/// `#[non_exhaustive]` enums and variants work as usual, since the generated
+/// code always lives in the same crate as the enum.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[non_exhaustive]
+/// #[err(subset(PortError: BadPort))]
+/// pub enum Error {
+///     /// bad port
+///     #[err(from, code = 1)]
+///     BadPort(std::num::ParseIntError),
+///     /// bad host
+///     #[non_exhaustive]
+///     #[err(code = 2)]
+///     BadHost { host: &'static str },
+/// }
+///
+/// let err = Error::BadHost { host: "fox" };
+/// assert_eq!(err.to_string(), "bad host: host: fox");
+/// assert_eq!(err.error_code(), 2);
+/// ```
+///
+/// # catch-all variants
+/// `#[err(other)]` marks a variant holding a boxed error as the catch-all
+/// for errors that don't deserve their own variant, like [`std::io::Error::other`].
+/// it is transparent, can be converted from, and gets an `other` constructor
+/// accepting anything that converts into the box. only one variant can be
+/// marked.
+/// ```rust
+/// use std::error::Error as _;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     #[err(other)]
+///     Other(Box<dyn std::error::Error + Send + Sync>),
+/// }
+///
+/// let err = Error::other("oh no");
+/// assert_eq!(err.to_string(), "oh no");
+///
+/// let err = Error::other(std::fmt::Error);
+/// let Error::Other(inner) = err else { unreachable!() };
+/// assert!(inner.downcast_ref::<std::fmt::Error>().is_some());
+///
+/// let boxed: Box<dyn std::error::Error + Send + Sync> = "fox".into();
+/// assert!(Error::from(boxed).source().is_none());
+/// ```
+///
+/// nothing requires the field to be a box, which `no_std` crates without
+/// `alloc` don't have. any type that is an error works, with `other` taking
+/// whatever converts into it.
+/// ```rust
+/// #![no_std]
+/// // linked for running the test, but not nameable as `std`
+/// extern crate std as _;
+///
+/// type Dyn = &'static (dyn core::error::Error + Send + Sync);
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     #[err(other)]
+///     Other(Dyn),
+/// }
+///
+/// # fn main() {
+/// let err = Error::other(&core::fmt::Error as Dyn);
+/// assert!(matches!(err, Error::Other(_)));
+/// # }
+/// ```
+///
+/// # infallible conversions
+/// `#[err(infallible_from)]` on the enum implements `From<Infallible>`, so
+/// `?` can be used on results that never fail. enums without any variants
+/// get this automatically.
+/// ```rust
+/// use std::convert::Infallible;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(infallible_from)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Never {}
+///
+/// fn port(s: &str) -> Result<u16, Error> {
+///     let s: String = s.parse::<String>()?;
+///     Ok(s.len() as u16)
+/// }
+///
+/// fn never() -> Result<(), Never> {
+///     Ok(Ok::<(), Infallible>(())?)
+/// }
+///
+/// assert_eq!(port("fox").unwrap(), 3);
+/// assert!(never().is_ok());
+/// ```
+///
+/// # converting into other errors
+/// `#[err(into = path::Error)]` on the enum implements `From` for converting
+/// into another error enum. unit variants become the variant of the same
+/// name, while `#[err(into = path::Error::Variant)]` wraps a variant's only
+/// field in the given variant instead. every variant must be covered one way
+/// or another.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// not found
+///     NotFound,
+///     /// io error
+///     Io(#[err(source)] std::io::Error),
+/// }
+///
+/// mod storage {
+///     #[derive(Debug, foxerror::FoxError)]
+///     #[err(into = super::Error)]
+///     pub enum Error {
+///         /// not found
+///         NotFound,
+///         /// disk failed
+///         #[err(into = super::Error::Io)]
+///         Disk(std::io::Error),
+///     }
+/// }
+///
+/// fn main() {
+///     let err: Error = storage::Error::NotFound.into();
+///     assert!(matches!(err, Error::NotFound));
+///     let disk = std::io::Error::other("disk on fire");
+///     let err: Error = storage::Error::Disk(disk).into();
+///     assert!(matches!(err, Error::Io(_)));
+/// }
+/// ```
+///
+/// for error types that aren't enums like this, such as ones from other
+/// crates, `#[err(with = path::to_fn)]` next to `into` converts every
+/// variant without an `into` of its own using the given function instead.
+/// ```rust
+/// use std::io;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(into = io::Error, with = to_io)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     /// io error
+///     #[err(into = io::Error::from)]
+///     Io(io::Error),
+/// }
+///
+/// fn to_io(err: Error) -> io::Error {
+///     io::Error::new(io::ErrorKind::InvalidInput, err)
+/// }
+///
+/// let err: io::Error = Error::BadPort(0).into();
+/// assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+/// assert_eq!(err.to_string(), "bad port: 0");
+///
+/// let err: io::Error = Error::Io(io::ErrorKind::NotFound.into()).into();
+/// assert_eq!(err.kind(), io::ErrorKind::NotFound);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// not found
+///     NotFound,
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(into = Error)]
+/// enum LocalError {
+///     /// not found
+///     NotFound,
+///     /// disk full
+///     DiskFull(u64),
+/// }
+/// ```
+///
+/// # opaque errors
+/// `#[err(opaque = PublicError)]` on the enum generates a public wrapper
+/// struct around it, so the error can be exposed without making its variants
+/// part of your api. `Display`, `Debug` and `Error` are forwarded to the enum,
+/// as are `exit_code`, `error_code` and `kind` when they exist. the wrapper's
+/// visibility can be set with `#[err(opaque_vis = "pub(crate)")]`.
+/// ```rust
+/// mod config {
+///     #[derive(Debug, foxerror::FoxError)]
+///     #[err(opaque = Error)]
+///     enum Inner {
+///         /// bad port
+///         #[err(code = 1)]
+///         BadPort(u16),
+///     }
+///
+///     pub fn load() -> Result<(), Error> {
+///         Err(Inner::BadPort(0).into())
+///     }
+/// }
+///
+/// let err: config::Error = config::load().unwrap_err();
+/// assert_eq!(err.to_string(), "bad port: 0");
+/// assert_eq!(format!("{err:?}"), "BadPort(0)");
+/// assert_eq!(err.error_code(), 1);
+/// ```
+///
+/// # boxed errors
+/// `#[err(boxed = BoxedError)]` on the enum generates a wrapper struct
+/// holding it in a `Box`, keeping results pointer-sized when the enum is
+/// large. it forwards `Display`, `Debug` and `Error`, and can be converted
+/// from the enum and anything the enum can be converted from.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(boxed = BoxedError)]
+/// pub enum Error {
+///     /// bad port
+///     #[err(from)]
+///     BadPort(std::num::ParseIntError),
+///     /// too big
+///     TooBig(#[err(list)] [u8; 256]),
+/// }
+///
+/// fn port(s: &str) -> Result<u16, BoxedError> {
+///     Ok(s.parse()?)
+/// }
+///
+/// assert_eq!(std::mem::size_of::<BoxedError>(), std::mem::size_of::<usize>());
+/// let err = port("fox").unwrap_err();
+/// assert_eq!(err.to_string(), "bad port: invalid digit found in string");
+/// assert!(matches!(err.into_inner(), Error::BadPort(_)));
+/// ```
+///
+/// # name clashes
+/// generated code refers to the enum as `Self` and to everything else by
+/// its full path, so enums and variants can share names with common items.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(display_fields)]
+/// enum Result {
+///     /// ok
+///     Ok,
+///     /// err
+///     Err(#[err(source)] Option),
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Option {
+///     /// some
+///     Some(u8),
+///     /// none
+///     None,
+/// }
+///
+/// assert_eq!(Result::Ok.to_string(), "ok");
+/// assert_eq!(Option::Some(1).to_string(), "some: 1");
+/// assert_eq!(Result::Err(Option::None).display_fields(), []);
+/// ```
+///
+/// # static tables
+/// `#[err(static_table)]` on an enum with only unit variants looks messages
+/// up in a static array by the variant's discriminant, instead of matching
+/// on the variant at all. the enum has to be `Copy`, and its variants can't
+/// set their own discriminants. enums with 64 or more variants look up plain
+/// messages on their own anyway, so that variants with the same fields share
+/// one arm and the output stays the same.
+/// ```rust
+/// #[derive(Debug, Clone, Copy, foxerror::FoxError)]
+/// #[err(static_table, terminator = ".")]
+/// enum Error {
+///     /// bad port
+///     BadPort,
+///     /// bad host
+///     BadHost,
+///     #[err(msg = "not found")]
+///     NotFound,
+/// }
+///
+/// assert_eq!(Error::BadPort.to_string(), "bad port.");
+/// assert_eq!(Error::NotFound.to_string(), "not found.");
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, Clone, Copy, foxerror::FoxError)]
+/// #[err(static_table)]
+/// enum Error {
+///     /// bad port
+///     BadPort = 1,
+///     /// bad host
+///     BadHost,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(static_table)]
+/// enum Error {
+///     /// bad port
+///     BadPort,
+/// }
+/// ```
+///
+/// without it, an enum with only unit variants still picks its message with
+/// a single match, and passes it to
+/// [`Formatter::pad`](core::fmt::Formatter::pad) so width and alignment work
+/// like they do for `str`.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     BadPort,
+///     /// bad host
+///     BadHost,
+/// }
+///
+/// assert_eq!(format!("[{:>10}]", Error::BadPort), "[  bad port]");
+/// assert_eq!(format!("[{:.3}]", Error::BadHost), "[bad]");
+/// ```
+///
+/// # stripping messages
+/// where every byte counts, `#[err(strip_messages(...))]` on the enum takes a
+/// cfg predicate, like `feature = "tiny"`, under which `Display` only writes
+/// each variant's code, or its name if the variants don't have codes. the
+/// messages are then left out of the generated code entirely, rather than
+/// just going unused. other generated items that work with the messages,
+/// such as `from_str` and the message constants, keep them.
+/// ```rust
+/// // all() is always true, standing in for a feature here
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(strip_messages(all()))]
+/// enum Error {
+///     /// a long message nobody will read
+///     #[err(code = 404)]
+///     NotFound,
+///     /// another one
+///     #[err(code = 500)]
+///     Broken { reason: &'static str },
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(strip_messages(all()))]
+/// enum Uncoded {
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// assert_eq!(Error::NotFound.to_string(), "404");
+/// assert_eq!(Error::Broken { reason: "fire" }.to_string(), "500");
+/// assert_eq!(Uncoded::BadPort(0).to_string(), "BadPort");
+/// ```
+///
+/// # no_std
+/// the generated code only refers to `core`, so it works in `no_std`
+/// crates. the exceptions are `path` fields, `boxed`, `display_fields`,
+/// `machine_readable`, `impl_exit_code` and `as_static`, which need `std`.
+/// ```rust
+/// #![no_std]
+/// // linked for running the test, but not nameable as `std`
+/// extern crate std as _;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(context_chain)]
+/// enum Error<'a> {
+///     /// bad port
+///     #[err(exit_code = 2)]
+///     BadPort(u16),
+///     /// took too long
+///     Timeout(#[err(duration)] core::time::Duration),
+///     /// missing
+///     Missing(#[err(list)] [&'a str; 2]),
+///     /// could not format
+///     Format(#[err(source)] core::fmt::Error),
+///     #[err(transparent, from)]
+///     Fmt(core::fmt::Error),
+/// }
+///
+/// # fn main() {
+/// let _ = Error::BadPort(0).exit_code();
+/// let _ = Error::Timeout(core::time::Duration::ZERO);
+/// let _ = Error::Missing(["a", "b"]);
+/// let _ = Error::Format(core::fmt::Error);
+/// let _ = Error::from(core::fmt::Error);
+/// # }
+/// ```
+///
+/// toolchains too old to have `core::error::Error` can implement a trait of
+/// their own instead with `#[err(error_trait = "path::to::Trait")]`. the
+/// implementation is empty, so the trait can't have required methods.
+/// sources and anything else handing the enum out as an `Error` are not
+/// available then.
+/// ```rust
+/// trait Fault: core::fmt::Debug + core::fmt::Display {}
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(error_trait = "Fault")]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// fn report(fault: &dyn Fault) -> String {
+///     fault.to_string()
+/// }
+///
+/// assert_eq!(report(&Error::BadPort(0)), "bad port: 0");
+/// ```
+///
+/// # writing into buffers
+/// `#[err(write_to)]` on the enum adds a `write_to` method writing the same
+/// message as `Display` into any `core::fmt::Write`, so that `no_std` code
+/// can format errors into a fixed buffer without allocating.
+/// ```rust
+/// struct Buf {
+///     bytes: [u8; 16],
+///     len: usize,
+/// }
+///
+/// impl core::fmt::Write for Buf {
+///     fn write_str(&mut self, s: &str) -> core::fmt::Result {
+///         let end = self.len + s.len();
+///         let dest = self.bytes.get_mut(self.len..end).ok_or(core::fmt::Error)?;
+///         dest.copy_from_slice(s.as_bytes());
+///         self.len = end;
+///         Ok(())
+///     }
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(write_to)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     /// the configuration file could not be found
+///     NotFound,
+/// }
+///
+/// let mut buf = Buf { bytes: [0; 16], len: 0 };
+/// Error::BadPort(0).write_to(&mut buf).unwrap();
+/// assert_eq!(&buf.bytes[..buf.len], b"bad port: 0");
+/// assert!(Error::NotFound.write_to(&mut buf).is_err());
+/// ```
+///
+/// `Display` doesn't call `write_to`, as both are generated from the same
+/// messages instead. that way `Display` can still hand its `Formatter` on to
+/// transparent variants, so that flags like `{:#}` reach the inner error,
+/// which a plain writer has no way to carry.
+///
+/// # declarative macros
+/// the derive also works on enums produced by `macro_rules!`.
+/// ```rust
+/// macro_rules! error_enum {
+///     ($name:ident { $($(#[$meta:meta])* $variant:ident($ty:ty)),* $(,)? }) => {
+///         #[derive(Debug, foxerror::FoxError)]
+///         enum $name {
+///             $($(#[$meta])* $variant($ty)),*
+///         }
+///     };
+/// }
+///
+/// error_enum!(Error {
+///     /// bad port
+///     BadPort(u16),
+///     #[err(msg = "bad host")]
+///     BadHost(&'static str),
+/// });
+///
+/// assert_eq!(format!("{}", Error::BadPort(0)), "bad port: 0");
+/// assert_eq!(format!("{}", Error::BadHost("fox")), "bad host: fox");
+/// ```
+///
+/// # hygiene
+/// every path in the generated code is fully qualified, so it keeps working
+/// under `#![no_implicit_prelude]` and next to macros that shadow `write!`
+/// and friends. fields are bound to generated names, so one called `f`
+/// doesn't get mixed up with the formatter.
+/// ```rust
+/// mod errors {
+///     #![no_implicit_prelude]
+///
+///     macro_rules! write {
+///         ($($t:tt)*) => {
+///             ::core::compile_error!("shadowed")
+///         };
+///     }
+///
+///     #[derive(::core::fmt::Debug, ::foxerror::FoxError)]
+///     #[err(error_kind, context_chain, display_fields, result_alias)]
+///     pub enum Error {
+///         /// bad port {port} on {f}
+///         BadPort { port: u16, f: &'static str },
+///         /// took too long
+///         Timeout(#[err(duration)] ::core::time::Duration),
+///         /// inner
+///         #[err(from)]
+///         Inner(#[err(source)] ::std::io::Error),
+///         #[err(transparent)]
+///         Fmt(::core::fmt::Error),
+///     }
+/// }
+/// use errors::Error;
+///
+/// let err = Error::BadPort { port: 0, f: "fox" };
+/// assert_eq!(err.to_string(), "bad port 0 on fox");
+/// ```
+///
+/// # generated modules
+/// `#[err(module = name)]` on the enum places every auxiliary item the derive
+/// generates (anything that isn't an implementation on the enum itself) in a
+/// module with that name instead of next to the enum, so they cannot collide
+/// with your own items. the module has the same visibility as the enum unless
+/// overridden with `#[err(module_vis = "pub(crate)")]`.
+/// ```rust
+/// mod parse {
+///     #[derive(Debug, foxerror::FoxError)]
+///     #[err(module = errors, module_vis = "pub(crate)")]
+///     pub enum Error {
+///         /// bad input
+///         BadInput,
+///     }
+/// }
+///
+/// use parse::errors as _;
+/// # fn main() {}
+/// ```
+///
+/// # sources
+/// mark a field with `#[err(source)]` to return it from `source`. source
+/// fields are left out of the message, since they are available through
+/// the error chain instead. boxed trait objects are supported as well.
+///
+/// adding `#[err(context_chain)]` to the enum appends every error in the
+/// chain to the message, each one indented a level deeper than the last.
+/// ```rust
+/// use std::error::Error as _;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Inner {
+///     /// middle error
+///     Middle(#[err(source)] std::fmt::Error),
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(context_chain)]
+/// enum Error {
+///     /// top level error
+///     Top {
+///         #[err(source)]
+///         inner: Inner,
+///         attempt: u8,
+///     },
+///     /// boxed
+///     Boxed(#[err(source)] Box<dyn std::error::Error + Send + Sync>),
+/// }
+///
+/// let err = Error::Top {
+///     inner: Inner::Middle(std::fmt::Error),
+///     attempt: 2,
+/// };
+/// assert_eq!(
+///     format!("{err}"),
+///     "top level error: attempt: 2\n  caused by: middle error\n    caused by: an error occurred when formatting an argument",
+/// );
+/// assert_eq!(format!("{}", Inner::Middle(std::fmt::Error)), "middle error");
+/// let boxed = Error::Boxed("oh no".into());
+/// assert_eq!(boxed.source().unwrap().to_string(), "oh no");
+/// ```
+///
+/// `#[err(with_depth)]` on the enum instead just counts the errors under
+/// it, for getting an idea of how deep an error goes without all of it.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Inner {
+///     /// middle error
+///     Middle(#[err(source)] std::fmt::Error),
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(with_depth)]
+/// enum Error {
+///     /// open failed
+///     Open(#[err(source)] Inner),
+///     /// write failed
+///     Write(#[err(source)] std::fmt::Error),
+///     /// closed
+///     Closed,
+/// }
+///
+/// let err = Error::Open(Inner::Middle(std::fmt::Error));
+/// assert_eq!(err.to_string(), "open failed (2 causes)");
+/// assert_eq!(Error::Write(std::fmt::Error).to_string(), "write failed (1 cause)");
+/// assert_eq!(Error::Closed.to_string(), "closed");
+/// ```
+///
+/// source fields that aren't errors are pointed out where they are
+/// declared, saying that the field has to implement `Error`.
+/// ```compile_fail
+/// #[derive(Debug)]
+/// struct NotAnError;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// oops
+///     Oops(#[err(source)] NotAnError),
+/// }
+/// ```
+///
+/// if a source's `Display` isn't very helpful, `#[err(source_debug)]` marks
+/// the field as the source while still showing it in the message using its
+/// `Debug` output.
+/// ```rust
+/// #[derive(Debug)]
+/// struct Opaque(u8);
+///
+/// impl std::fmt::Display for Opaque {
+///     fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         Ok(())
+///     }
+/// }
+///
+/// impl std::error::Error for Opaque {}
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// hidden
+///     Hidden(#[err(source)] Opaque),
+///     /// shown
+///     Shown(#[err(source_debug)] Opaque),
+/// }
+///
+/// assert_eq!(format!("{}", Error::Hidden(Opaque(1))), "hidden");
+/// assert_eq!(format!("{}", Error::Shown(Opaque(1))), "shown: Opaque(1)");
+/// ```
+///
+/// sources can be borrowed too. since `source` can only return errors that
+/// are `'static`, a borrowed trait object has to say so, as in
+/// `&'a (dyn Error + 'static)`.
+/// ```rust
+/// use std::error::Error as _;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error<'a> {
+///     /// wrapped
+///     Wrap(#[err(source)] &'a (dyn std::error::Error + 'static)),
+/// }
+///
+/// let inner = std::fmt::Error;
+/// let err = Error::Wrap(&inner);
+/// assert_eq!(err.to_string(), "wrapped");
+/// assert!(err.source().unwrap().is::<std::fmt::Error>());
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error<'a> {
+///     /// wrapped
+///     Wrap(#[err(source)] &'a dyn std::error::Error),
+/// }
+/// ```
+///
+/// # leaking borrowed strings
+/// `#[err(as_static)]` on variants generates a `leak` method, turning an
+/// error borrowing strings into one that lives forever by leaking the `&str`
+/// fields of those variants. this is meant for errors that are kept around
+/// until the program exits, since the memory is never freed.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error<'a> {
+///     /// not found
+///     #[err(as_static)]
+///     NotFound { path: &'a str, attempts: u8 },
+///     /// unknown
+///     Unknown,
+/// }
+///
+/// fn remember(err: Error<'static>) -> Box<dyn std::error::Error> {
+///     Box::new(err)
+/// }
+///
+/// let path = String::from("fox.toml");
+/// let err = remember(Error::NotFound { path: &path, attempts: 2 }.leak());
+/// drop(path);
+/// assert_eq!(err.to_string(), "not found: path: fox.toml, attempts: 2");
+/// assert_eq!(remember(Error::Unknown.leak()).to_string(), "unknown");
+/// ```
+///
+/// # translation keys
+/// `#[err(key = "...")]` on every variant generates a `message_key` method,
+/// so that tools can look up translations of the message while `Display`
+/// keeps showing the message as written.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// not found
+///     #[err(key = "error.not_found")]
+///     NotFound { path: &'static str },
+///     /// bad port
+///     #[err(key = "error.bad_port")]
+///     BadPort(u16),
+/// }
+///
+/// let err = Error::NotFound { path: "fox.toml" };
+/// assert_eq!(err.message_key(), "error.not_found");
+/// assert_eq!(err.to_string(), "not found: path: fox.toml");
+/// assert_eq!(Error::BadPort(0).message_key(), "error.bad_port");
+/// ```
+///
+/// # variant indexes
+/// `#[err(variant_index)]` on the enum generates a `variant_index` method,
+/// returning the position of the variant in the enum starting at 0. this is
+/// cheaper than comparing names when looking things up in a table.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(variant_index)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     /// not found
+///     NotFound { path: &'static str },
+///     /// unknown
+///     Unknown,
+/// }
+///
+/// const HINTS: [&str; 3] = ["use a port below 65536", "check the path", "try again"];
+///
+/// assert_eq!(Error::BadPort(0).variant_index(), 0);
+/// assert_eq!(Error::NotFound { path: "/" }.variant_index(), 1);
+/// assert_eq!(Error::Unknown.variant_index(), 2);
+/// assert_eq!(HINTS[Error::Unknown.variant_index()], "try again");
+/// ```
+///
+/// `#[err(variants_count)]` adds a `VARIANTS_COUNT` constant holding the
+/// number of variants, for sizing tables like this one. variants left out by
+/// `cfg` aren't counted.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(variant_index, variants_count)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     /// tls failed
+///     #[cfg(feature = "tls")]
+///     Tls,
+///     /// unknown
+///     Unknown,
+/// }
+///
+/// const HINTS: [&str; Error::VARIANTS_COUNT] = ["use a port below 65536", "try again"];
+///
+/// assert_eq!(Error::VARIANTS_COUNT, 2);
+/// assert_eq!(HINTS[Error::BadPort(0).variant_index()], "use a port below 65536");
+/// ```
+///
+/// # parsing messages
+/// `#[err(impl_from_display)]` on the enum implements `FromStr`, turning a
+/// message back into the error that displayed it. every displayed field's
+/// type has to implement `FromStr` too, and implicit fields are filled in
+/// like usual. failing to match any variant gives a `Parse{Enum}Error`, or
+/// whatever `#[err(impl_from_display = Name)]` names it instead. like the
+/// other generated items, it goes in the enum's `module` if there is one.
+///
+/// this is best-effort: variants are tried in order, and ones that can't be
+/// reversed are skipped, such as transparent variants, variants with hidden
+/// sources, templates, field placeholders or fields not shown with
+/// `Display`. values containing `", "` may be split in the wrong place.
+/// ```rust
+/// #[derive(Debug, PartialEq, foxerror::FoxError)]
+/// #[err(impl_from_display)]
+/// enum Error {
+///     /// request timed out
+///     Timeout { after_ms: u64 },
+///     /// unknown user
+///     UnknownUser(#[err(quote)] String),
+///     /// not found
+///     NotFound,
+/// }
+///
+/// let err = Error::Timeout { after_ms: 5000 };
+/// assert_eq!(err.to_string(), "request timed out: after_ms: 5000");
+/// assert_eq!(err.to_string().parse(), Ok(err));
+/// assert_eq!(
+///     r#"unknown user: "fox""#.parse(),
+///     Ok(Error::UnknownUser("fox".into()))
+/// );
+/// assert_eq!("not found".parse(), Ok(Error::NotFound));
+/// assert_eq!("not found: 3".parse::<Error>(), Err(ParseErrorError));
+///
+/// #[derive(Debug, PartialEq, foxerror::FoxError)]
+/// #[err(impl_from_display = UnknownMessage)]
+/// enum ParseError {
+///     /// unexpected end of input
+///     End,
+/// }
+///
+/// assert_eq!("fox".parse::<ParseError>(), Err(UnknownMessage));
+/// ```
+///
+/// # structured fields
+/// `#[err(display_fields)]` on the enum generates a `display_fields` method
+/// returning each shown field's name and displayed value, for logging them
+/// in a structured way. fields of tuple variants are named by their index.
+/// this needs `std`.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(display_fields)]
+/// enum Error {
+///     /// bad request
+///     BadRequest { method: &'static str, status: u16 },
+///     /// not found
+///     NotFound(&'static str),
+///     /// gone
+///     Gone,
+/// }
+///
+/// let err = Error::BadRequest { method: "GET", status: 400 };
+/// assert_eq!(
+///     err.display_fields(),
+///     [("method", "GET".to_string()), ("status", "400".to_string())],
+/// );
+/// assert_eq!(
+///     Error::NotFound("/fox").display_fields(),
+///     [("0", "/fox".to_string())],
+/// );
+/// assert!(Error::Gone.display_fields().is_empty());
+/// ```
+///
+/// # machine-readable errors
+/// `#[err(machine_readable)]` on the enum generates an `ErrorInfo` struct
+/// (named after the enum) holding the variant name, message, error code and
+/// displayed fields, along with a `to_info` method to build it. the message
+/// is the one written on the variant, so any `{field}` placeholders are left
+/// in for the fields to be filled in from `fields`.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(machine_readable)]
+/// enum Error {
+///     /// bad port
+///     BadPort { port: u16, host: &'static str },
+/// }
+///
+/// let info = Error::BadPort { port: 0, host: "localhost" }.to_info();
+/// assert_eq!(info.variant, "BadPort");
+/// assert_eq!(info.message, "bad port");
+/// assert_eq!(info.code, None);
+/// assert_eq!(
+///     info.fields,
+///     [("port", "0".to_string()), ("host", "localhost".to_string())],
+/// );
+/// ```
+///
+/// with the `serde` feature enabled, adding `#[err(serde)]` also derives
+/// `Serialize` for the struct, next to the enum's own implementation
+/// described below.
+#[cfg_attr(feature = "serde", doc = "```rust")]
+#[cfg_attr(not(feature = "serde"), doc = "```ignore")]
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(machine_readable, serde)]
+/// enum Error {
+///     /// not found
+///     #[err(code = 404)]
+///     NotFound(&'static str),
+/// }
+///
+/// let info = Error::NotFound("/fox").to_info();
+/// assert_eq!(
+///     serde_json::to_string(&info).unwrap(),
+///     r#"{"variant":"NotFound","message":"not found","code":404,"fields":[["0","/fox"]]}"#,
+/// );
+/// ```
+///
+/// # serde
+/// with the `serde` feature enabled, `#[err(serde)]` on the enum implements
+/// `Serialize`, giving the variant's name, the message as it is displayed,
+/// and the fields with their own `Serialize` implementations. sources and
+/// fields left out of the message, like those marked `#[err(skip)]`, are
+/// left out here too.
+#[cfg_attr(feature = "serde", doc = "```rust")]
+#[cfg_attr(not(feature = "serde"), doc = "```ignore")]
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(serde)]
+/// enum Error {
+///     /// timed out after {secs}s
+///     Timeout {
+///         secs: u64,
+///         #[err(skip)]
+///         token: &'static str,
+///     },
+///     /// bad port
+///     BadPort(u16, #[err(debug)] Option<&'static str>),
+///     /// read failed
+///     Read(#[err(source)] std::io::Error),
+/// }
+///
+/// let err = Error::Timeout { secs: 30, token: "hunter2" };
+/// assert_eq!(
+///     serde_json::to_string(&err).unwrap(),
+///     r#"{"variant":"Timeout","message":"timed out after 30s","fields":{"secs":30}}"#,
+/// );
+/// assert_eq!(
+///     serde_json::to_string(&Error::BadPort(0, None)).unwrap(),
+///     r#"{"variant":"BadPort","message":"bad port: 0, None","fields":{"0":0,"1":null}}"#,
+/// );
+/// let err = Error::Read(std::io::ErrorKind::NotFound.into());
+/// assert_eq!(
+///     serde_json::to_string(&err).unwrap(),
+///     r#"{"variant":"Read","message":"read failed","fields":{}}"#,
+/// );
+/// ```
+///
+/// # display hooks
+/// `#[err(on_display = "path::to::hook")]` on the enum calls a
+/// `fn(&'static str)` with the variant's name every time an error is
+/// displayed, which is handy for counting errors in metrics.
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static SHOWN: AtomicUsize = AtomicUsize::new(0);
+///
+/// fn record(variant: &'static str) {
+///     assert_eq!(variant, "BadPort");
+///     SHOWN.fetch_add(1, Ordering::Relaxed);
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(on_display = "record")]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// let _ = Error::BadPort(0).to_string();
+/// let _ = Error::BadPort(1).to_string();
+/// assert_eq!(SHOWN.load(Ordering::Relaxed), 2);
+/// ```
+///
+/// # conditional messages
+/// `#[err(fmt_if = path::to::hook)]` on a variant calls a
+/// `fn(&Error) -> Option<impl Display>` before displaying it, using what it
+/// returns in place of the usual message if it is `Some`.
+/// ```rust
+/// fn well_known(err: &Error) -> Option<&'static str> {
+///     match err {
+///         Error::BadPort(22) => Some("ssh is not allowed here"),
+///         _ => None,
+///     }
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     #[err(fmt_if = well_known)]
+///     BadPort(u16),
+/// }
+///
+/// assert_eq!(Error::BadPort(22).to_string(), "ssh is not allowed here");
+/// assert_eq!(Error::BadPort(8080).to_string(), "bad port: 8080");
+/// ```
+///
+/// # error kinds
+/// `#[err(error_kind)]` on the enum generates a fieldless `ErrorKind` enum
+/// (named after the enum) with a variant for each of its variants, like
+/// [`std::io::ErrorKind`]. errors can be converted into their kind with
+/// `From` or the `kind` method. when variants have error codes, those are
+/// used as the kind's discriminants, so no two of them can be the same.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(error_kind)]
+/// enum Error {
+///     /// bad port
+///     #[err(code = 400)]
+///     BadPort(u16),
+///     /// not found
+///     #[err(code = 404)]
+///     NotFound { path: &'static str },
+/// }
+///
+/// let err = Error::NotFound { path: "/fox" };
+/// assert_eq!(err.kind(), ErrorKind::NotFound);
+/// assert_eq!(ErrorKind::from(&Error::BadPort(0)), ErrorKind::BadPort);
+/// assert_eq!(err.kind() as i32, 404);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(error_kind)]
+/// enum Error {
+///     /// not found
+///     #[err(code = 404)]
+///     NotFound,
+///     /// gone
+///     #[err(code = 404)]
+///     Gone,
+/// }
+/// ```
+///
+/// # error metadata
+/// `#[err(error_meta)]` on the enum implements `ErrorMeta` from the
+/// `foxerror-traits` crate, for handling different error enums the same way
+/// through `&dyn ErrorMeta`. a variant's severity is set with
+/// `#[err(severity = "warning")]`, and can be `warning`, `error` (the default)
+/// or `fatal`.
+/// ```rust
+/// use foxerror_traits::{ErrorMeta, Severity};
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(error_meta)]
+/// enum Error {
+///     /// bad port
+///     #[err(code = 1, severity = "warning")]
+///     BadPort(u16),
+///     /// disk on fire
+///     #[err(code = 2, severity = "fatal")]
+///     Fire,
+///     /// not found
+///     #[err(code = 3)]
+///     NotFound,
+/// }
+///
+/// fn report(err: &dyn ErrorMeta) -> String {
+///     format!("{:?} {} ({:?}): {}", err.severity(), err.name(), err.code(), err)
+/// }
+///
+/// assert_eq!(report(&Error::BadPort(0)), "Warning BadPort (Some(1)): bad port: 0");
+/// assert_eq!(report(&Error::Fire), "Fatal Fire (Some(2)): disk on fire");
+/// assert_eq!(Error::NotFound.severity(), Severity::Error);
+/// ```
+///
+/// # adding errors together
+/// `#[err(additive)]` on the enum implements `Add`, so that `a + b` collects
+/// both errors into a `MultiError` from the `foxerror-traits` crate. more
+/// errors can be added onto that, and it shows them all separated by `; `.
+/// ```rust
+/// use foxerror_traits::MultiError;
+///
+/// #[derive(Debug, PartialEq, foxerror::FoxError)]
+/// #[err(additive)]
+/// enum Error {
+///     /// missing name
+///     NoName,
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// let errors = Error::NoName + Error::BadPort(0) + Error::BadPort(1);
+/// assert_eq!(errors.to_string(), "missing name; bad port: 0; bad port: 1");
+/// assert_eq!(errors, MultiError(vec![Error::NoName, Error::BadPort(0), Error::BadPort(1)]));
+/// ```
+///
+/// `error_meta` and `additive`, like `#[foxerror::context]` with a message,
+/// refer to the `foxerror-traits` crate as `::foxerror_traits`. when it is re-exported
+/// from another crate or renamed, point at it with
+/// `#[err(traits_crate = path)]` on the enum, or with `traits_crate = path`
+/// after the message for `context`.
+/// ```rust
+/// mod facade {
+///     pub mod traits {
+///         pub use foxerror_traits::{Context, ErrorMeta, MultiError, Severity};
+///     }
+/// }
+///
+/// use facade::traits::{ErrorMeta, Severity};
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(traits_crate = facade::traits, error_meta, additive)]
+/// enum Error {
+///     /// bad port
+///     #[err(severity = "warning")]
+///     BadPort(u16),
+///     #[err(transparent, from)]
+///     Context(facade::traits::Context),
+/// }
+///
+/// #[foxerror::context("while loading the config", traits_crate = facade::traits)]
+/// fn load_config(port: &str) -> Result<u16, Error> {
+///     Ok(port.parse()?)
+/// }
+///
+/// let err = load_config("fox").unwrap_err();
+/// assert_eq!(err.severity(), Severity::Error);
+/// assert_eq!(Error::BadPort(0).severity(), Severity::Warning);
+/// assert_eq!(
+///     (err + Error::BadPort(0)).to_string(),
+///     "while loading the config: invalid digit found in string; bad port: 0",
+/// );
+/// ```
+///
+/// # error codes
+/// giving every variant an `#[err(code = 404)]` generates an `error_code`
+/// method. with `#[err(hash_by_code)]` on the enum, `Hash`, `PartialEq` and
+/// `Eq` are implemented by comparing codes alone, which makes it easy to
+/// group errors that only differ in their fields.
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(hash_by_code)]
+/// enum Error {
+///     /// not found
+///     #[err(code = 404)]
+///     NotFound(&'static str),
+///     /// teapot
+///     #[err(code = 418)]
+///     Teapot,
+/// }
+///
+/// assert_eq!(Error::NotFound("/fox").error_code(), 404);
+/// assert_eq!(Error::NotFound("/fox"), Error::NotFound("/den"));
+///
+/// let mut seen = HashMap::new();
+/// for err in [Error::NotFound("/a"), Error::Teapot, Error::NotFound("/b")] {
+///     *seen.entry(err).or_insert(0) += 1;
+/// }
+/// assert_eq!(seen[&Error::NotFound("/c")], 2);
+/// assert_eq!(seen[&Error::Teapot], 1);
+/// ```
+///
+/// # trait assertions
+/// `#[err(assert = "Send + Sync + 'static")]` on the enum fails to compile
+/// right at the enum if it does not satisfy the given bounds, which stops
+/// someone from sneaking an `Rc` into your error a few releases later.
+/// enums with type parameters are not checked, since whether they satisfy
+/// the bounds depends on the types they are used with.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(assert = "Send + Sync + 'static")]
+/// enum Error {
+///     /// bad name
+///     BadName(std::sync::Arc<str>),
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(assert = "Send + Sync")]
+/// enum Error {
+///     /// bad name
+///     BadName(std::rc::Rc<str>),
+/// }
+/// ```
+///
+/// # anyhow compatibility
+/// with the `anyhow` feature enabled, `#[err(anyhow)]` on the enum checks
+/// that it is `Send + Sync + 'static` so that `anyhow::Error::from` accepts
+/// it, failing to compile right at the enum otherwise. enums with type
+/// parameters are not checked.
+#[cfg_attr(feature = "anyhow", doc = "```rust")]
+#[cfg_attr(not(feature = "anyhow"), doc = "```ignore")]
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(anyhow)]
+/// enum Error {
+///     /// shared state
+///     Shared(std::sync::Arc<str>),
+/// }
+/// ```
+///
+#[cfg_attr(feature = "anyhow", doc = "```compile_fail")]
+#[cfg_attr(not(feature = "anyhow"), doc = "```ignore")]
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(anyhow)]
+/// enum Error {
+///     /// not so shared state
+///     Shared(std::rc::Rc<str>),
+/// }
+/// ```
+///
+/// # pretty printing
+/// `#[err(pretty)]` on the enum shows each field on its own line when
+/// formatted with the alternate flag (`{:#}`), keeping the usual compact
+/// message otherwise. variants whose message is written some other way,
+/// like with a template or field placeholders, look the same either way.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(pretty)]
+/// enum Error {
+///     /// could not connect
+///     Connect { host: &'static str, port: u16 },
+///     /// not found
+///     NotFound,
+/// }
+///
+/// let err = Error::Connect { host: "localhost", port: 80 };
+/// assert_eq!(format!("{err}"), "could not connect: host: localhost, port: 80");
+/// assert_eq!(
+///     format!("{err:#}"),
+///     "could not connect\n  host: localhost\n  port: 80",
+/// );
+/// assert_eq!(format!("{:#}", Error::NotFound), "not found");
+/// ```
+///
+/// # colors
+/// with the `color` feature enabled, `#[err(color)]` on the enum shows
+/// messages in red when formatted with the alternate flag (`{:#}`). since
+/// `Display` has no way of knowing whether it is writing to a terminal, it is
+/// up to you to only use the alternate flag when it is.
+#[cfg_attr(feature = "color", doc = "```rust")]
+#[cfg_attr(not(feature = "color"), doc = "```ignore")]
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(color)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// assert_eq!(format!("{}", Error::BadPort(0)), "bad port: 0");
+/// assert_eq!(format!("{:#}", Error::BadPort(0)), "\x1b[31mbad port: 0\x1b[0m");
+/// ```
+///
+/// # tracing
+/// with the `tracing` feature enabled, `#[err(tracing_event = "warn")]` on
+/// the enum emits a [`tracing`](https://docs.rs/tracing) event every time
+/// an error is displayed, with the variant's name as `variant` and its
+/// translation key as `key` if it has one. the message itself isn't
+/// recorded, since that would mean displaying it twice. variants can use a
+/// different level with
+/// `#[err(tracing_level = "error")]`, or have an event of their own when the
+/// enum has none. your crate needs to depend on `tracing` itself.
+#[cfg_attr(feature = "tracing", doc = "```rust")]
+#[cfg_attr(not(feature = "tracing"), doc = "```ignore")]
+/// use std::sync::{Arc, Mutex};
+/// use tracing::{field::{Field, Visit}, span, Event, Level, Metadata, Subscriber};
+///
+/// #[derive(Default)]
+/// struct Record(Mutex<Vec<(Level, String)>>);
+///
+/// impl Visit for &Record {
+///     fn record_str(&mut self, field: &Field, value: &str) {
+///         if field.name() == "variant" {
+///             self.0.lock().unwrap().last_mut().unwrap().1 = value.to_string();
+///         }
+///     }
+///
+///     fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+/// }
+///
+/// impl Subscriber for Record {
+///     fn enabled(&self, _: &Metadata<'_>) -> bool { true }
+///     fn new_span(&self, _: &span::Attributes<'_>) -> span::Id { span::Id::from_u64(1) }
+///     fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+///     fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+///     fn enter(&self, _: &span::Id) {}
+///     fn exit(&self, _: &span::Id) {}
+///     fn event(&self, event: &Event<'_>) {
+///         self.0.lock().unwrap().push((*event.metadata().level(), String::new()));
+///         event.record(&mut &*self);
+///     }
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(tracing_event = "warn")]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     /// disk on fire
+///     #[err(tracing_level = "error")]
+///     DiskOnFire,
+/// }
+///
+/// let record = Arc::new(Record::default());
+/// tracing::subscriber::with_default(record.clone(), || {
+///     assert_eq!(Error::BadPort(0).to_string(), "bad port: 0");
+///     assert_eq!(Error::DiskOnFire.to_string(), "disk on fire");
+/// });
+/// assert_eq!(
+///     *record.0.lock().unwrap(),
+///     [
+///         (Level::WARN, "BadPort".to_string()),
+///         (Level::ERROR, "DiskOnFire".to_string()),
+///     ],
+/// );
+/// ```
+///
+/// # invalid attributes
+/// mistakes in `#[err(...)]` arguments are reported as compile errors
+/// pointing at the argument, rather than as a panic in the macro.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     #[err(exit_code)]
+///     BadPort(u16),
+/// }
+/// ```
+///
+/// values have to be of the right form as well, like a string literal for
+/// `msg`.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     #[err(msg = 42)]
+///     BadPort(u16),
+/// }
+/// ```
+///
+/// flags can be given on their own or as `= true` or `= false`, which makes
+/// it easy to turn one off without removing it.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(quote_values = false)]
+/// enum Error {
+///     /// bad host
+///     BadHost(String),
+///     /// read failed
+///     #[err(from = false)]
+///     Read(std::io::Error),
+/// }
+///
+/// impl From<std::io::Error> for Error {
+///     fn from(err: std::io::Error) -> Self {
+///         Self::Read(err)
+///     }
+/// }
+///
+/// assert_eq!(Error::BadHost("::1".into()).to_string(), "bad host: ::1");
+/// ```
+///
+/// anything else is an error.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// read failed
+///     #[err(from = "nope")]
+///     Read(std::io::Error),
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(context_chain = 5)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+/// }
+/// ```
+///
+/// unknown arguments are rejected too, suggesting a similar one if there is
+/// any.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     #[err(mgs = "bad port")]
+///     BadPort(u16),
+/// }
+/// ```
+///
+/// to stay compatible with newer versions of foxerror that accept more
+/// arguments, `#[err(allow_unknown_attrs)]` on the enum ignores the unknown
+/// ones on it, its variants and their fields instead. known arguments used in
+/// the wrong place are still an error.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(allow_unknown_attrs, from_the_future)]
+/// enum Error {
+///     /// bad port
+///     #[err(exit_code = 2, shiny = "yes")]
+///     BadPort(#[err(sparkle)] u16),
+/// }
+///
+/// assert_eq!(Error::BadPort(0).to_string(), "bad port: 0");
+/// ```
+///
+/// a field whose type can't be shown the way it is asked to, like one
+/// without `Display`, is pointed out at the field itself.
+/// ```compile_fail
+/// #[derive(Debug)]
+/// struct Port(u16);
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     BadPort(Port),
+/// }
+/// ```
+///
+/// only one variant can be converted from any given type, and every variant
+/// marked `from` with the same type is listed in the error.
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// read failed
+///     #[err(from)]
+///     Read(std::io::Error),
+///     /// write failed
+///     #[err(from)]
+///     Write(std::io::Error),
+/// }
+/// ```
+///
+/// # experimental error methods
+/// with the `nightly` feature enabled, `#[err(std_error_v2)]` on the enum
+/// adds methods mirroring proposed additions to the `Error` trait, currently
+/// `sources()` from `error_iter`. these follow the unstable api and may
+/// change along with it.
+#[cfg_attr(feature = "nightly", doc = "```rust")]
+#[cfg_attr(not(feature = "nightly"), doc = "```ignore")]
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Inner {
+///     /// disk on fire
+///     Fire,
+/// }
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(std_error_v2)]
+/// enum Error {
+///     /// could not save
+///     Save(#[err(source)] Inner),
+/// }
+///
+/// let err = Error::Save(Inner::Fire);
+/// let chain: Vec<_> = err.sources().map(|e| e.to_string()).collect();
+/// assert_eq!(chain, ["could not save", "disk on fire"]);
+/// ```
+///
+/// # legacy descriptions
+/// with the `legacy` feature enabled, the deprecated `Error::description` is
+/// implemented for code that still calls it, returning the message of unit
+/// variants and the name of any other variant.
+#[cfg_attr(feature = "legacy", doc = "```rust")]
+#[cfg_attr(not(feature = "legacy"), doc = "```ignore")]
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// not found
+///     NotFound,
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// #[allow(deprecated)]
+/// fn describe(err: &dyn std::error::Error) -> &str {
+///     err.description()
+/// }
+///
+/// assert_eq!(describe(&Error::NotFound), "not found");
+/// assert_eq!(describe(&Error::BadPort(0)), "BadPort");
+/// ```
+#[proc_macro_derive(FoxError, attributes(err))]
+pub fn foxerror(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    foxerror_core::derive(input.into()).into()
+}
+
+/// derive only [`Display`](core::fmt::Display) for an enum
+///
+/// takes the same `#[err(...)]` arguments as [`FoxError`] and shows errors
+/// the same way, but leaves out the `Error` implementation and everything
+/// else, so they can come from somewhere else instead, like another derive.
+///
+/// ```rust
+/// #[derive(Debug, foxerror::FoxErrorDisplay)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     /// not found
+///     NotFound,
+/// }
+///
+/// impl std::error::Error for Error {}
+///
+/// assert_eq!(Error::BadPort(0).to_string(), "bad port: 0");
+/// assert_eq!(Error::NotFound.to_string(), "not found");
+/// ```
+#[proc_macro_derive(FoxErrorDisplay, attributes(err))]
+pub fn foxerror_display(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    foxerror_core::derive_display(input.into()).into()
+}
+
+/// declare an error enum with inline messages
+///
+/// expands to the enum itself with `Debug` and [`FoxError`] derived,
+/// turning a string literal before a variant into its message.
+/// everything else, including nested `#[err(...)]` attributes, is passed
+/// through untouched.
+///
+/// ```rust
+/// foxerror::fox! {
+///     #[derive(PartialEq)]
+///     pub enum CliError {
+///         "could not read file" ReadFailed { path: &'static str },
+///         "invalid port" #[err(from)] BadPort(u16),
+///         /// doc comments still work too
+///         Unknown,
+///     }
+/// }
+///
+/// assert_eq!(
+///     format!("{}", CliError::ReadFailed { path: "/etc/fox" }),
+///     "could not read file: path: /etc/fox",
+/// );
+/// assert_eq!(format!("{}", CliError::from(0)), "invalid port: 0");
+/// assert_eq!(CliError::from(80), CliError::BadPort(80));
+/// assert_eq!(format!("{}", CliError::Unknown), "doc comments still work too");
+/// ```
+///
+/// inline messages double as documentation for variants without doc
+/// comments, so they show up in rustdoc as well.
+/// ```rust
+/// #[deny(missing_docs)]
+/// /// errors
+/// pub mod errors {
+///     foxerror::fox! {
+///         /// an error
+///         pub enum Error {
+///             "oh no" OhNo,
+///             /// oh yes
+///             OhYes,
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// when foxerror is re-exported from another crate or renamed, point at it
+/// with `#[err(crate = path)]` on the enum. this works for
+/// [`fox_union!`](fox_union) too.
+/// ```rust
+/// mod facade {
+///     pub mod errors {
+///         pub use foxerror::FoxError;
+///     }
+/// }
+///
+/// foxerror::fox! {
+///     #[err(crate = facade::errors)]
+///     enum Error {
+///         "oh no" OhNo,
+///     }
+/// }
+///
+/// assert_eq!(format!("{}", Error::OhNo), "oh no");
+/// ```
+#[proc_macro]
+pub fn fox(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    foxerror_core::fox(input.into()).into()
+}
+
+/// derive [`FoxError`] in a way that may change the enum
+///
+/// takes the same `#[err(...)]` arguments as the derive, either directly or
+/// on the enum, and also derives `Debug` if it isn't already. with
+/// `implicit_location`, every variant with fields gets an implicit
+/// `location` field recording where it was converted from using
+/// [`core::panic::Location`], added at the end for tuple variants.
+///
+/// ```rust
+/// #[foxerror::error(implicit_location)]
+/// enum Error {
+///     /// bad port
+///     #[err(from)]
+///     BadPort(std::num::ParseIntError),
+///     /// not found
+///     NotFound { path: &'static str },
+///     /// unknown
+///     Unknown,
+/// }
+///
+/// fn port(s: &str) -> Result<u16, Error> {
+///     Ok(s.parse()?)
+/// }
+///
+/// let err = port("fox").unwrap_err();
+/// assert_eq!(err.to_string(), "bad port: invalid digit found in string");
+/// let Error::BadPort(_, location) = err else { unreachable!() };
+/// assert_eq!(location.file(), file!());
+/// assert!(format!("{:?}", Error::Unknown).contains("Unknown"));
+/// ```
+///
+/// variants can also be gated on a cargo feature with
+/// `#[err(feature = "...")]`, which becomes a `#[cfg(feature = "...")]`
+/// before the derive sees the enum. this works in [`fox!`](fox) as well,
+/// while the derive on its own asks for a plain `cfg` instead.
+/// ```rust
+/// #[foxerror::error]
+/// enum Error {
+///     /// not found
+///     NotFound,
+///     /// tls failed
+///     #[err(feature = "tls")]
+///     Tls(std::io::Error),
+/// }
+///
+/// // this crate has no tls feature, so only one variant is left
+/// match Error::NotFound {
+///     Error::NotFound => {}
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn error(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    foxerror_core::error(attr.into(), item.into()).into()
+}
+
+/// wrap every error returned from a function in a variant
+///
+/// takes a path to a tuple variant (or anything else callable with the
+/// original error), or a struct variant with the field the error goes in,
+/// and applies it to the `Err` the function body produces, whether it came
+/// from `?`, an early `return Err(...)`, or the final expression. the type the body's errors get converted into is inferred
+/// from the variant's field, so `?` keeps working as usual. `async fn` is
+/// supported too.
+///
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not parse the config
+///     LoadConfig(std::num::ParseIntError),
+///     /// could not fetch the config
+///     Fetch(Box<dyn std::error::Error + Send + Sync>),
+/// }
+///
+/// #[foxerror::context(Error::LoadConfig)]
+/// fn load_config(port: &str) -> Result<u16, Error> {
+///     let port = port.parse()?;
+///     if port == 0 {
+///         return Err("".parse::<u16>().unwrap_err());
+///     }
+///     Ok(port)
+/// }
+///
+/// #[foxerror::context(Error::Fetch)]
+/// fn fetch(name: &str) -> Result<String, Error> {
+///     if name.is_empty() {
+///         Err("empty name")?;
+///     }
+///     Ok(format!("{name}.toml"))
+/// }
+///
+/// #[foxerror::context(Error::LoadConfig)]
+/// async fn load_config_async(port: &str) -> Result<u16, Error> {
+///     Ok(port.parse()?)
+/// }
+/// # let _ = load_config_async("8080");
+///
+/// assert_eq!(load_config("8080").unwrap(), 8080);
+/// assert_eq!(
+///     format!("{}", load_config("fox").unwrap_err()),
+///     "could not parse the config: invalid digit found in string",
+/// );
+/// assert!(matches!(load_config("0"), Err(Error::LoadConfig(_))));
+/// assert_eq!(fetch("fox").unwrap(), "fox.toml");
+/// assert_eq!(
+///     format!("{}", fetch("").unwrap_err()),
+///     "could not fetch the config: empty name",
+/// );
+/// ```
+///
+/// generic functions work the same, including bounds with arrows of their
+/// own, and so do functions returning `impl Trait`.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not load
+///     Load(std::num::ParseIntError),
+/// }
+///
+/// #[foxerror::context(Error::Load)]
+/// fn parse<F: Fn() -> u8>(s: &str, f: F) -> Result<u8, Error>
+/// where
+///     F: Copy,
+/// {
+///     Ok(s.parse::<u8>()? + f())
+/// }
+///
+/// assert_eq!(parse("1", || 2).unwrap(), 3);
+/// assert!(parse("fox", || 2).is_err());
+///
+/// #[foxerror::context(Error::Load)]
+/// fn parse_all(s: &str) -> Result<impl Iterator<Item = u8>, Error> {
+///     let all: Vec<u8> = s.split(',').map(str::parse).collect::<Result<_, _>>()?;
+///     Ok(all.into_iter())
+/// }
+///
+/// assert_eq!(parse_all("1,2").unwrap().sum::<u8>(), 3);
+/// ```
+///
+/// struct variants name the field the error goes in. ones with other fields
+/// to fill in need a closure instead.
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not load the config ({source})
+///     LoadConfig {
+///         #[err(source)]
+///         source: std::num::ParseIntError,
+///     },
+/// }
+///
+/// #[foxerror::context(Error::LoadConfig { source })]
+/// fn load_config(port: &str) -> Result<u16, Error> {
+///     Ok(port.parse()?)
+/// }
+///
+/// assert_eq!(load_config("8080").unwrap(), 8080);
+/// assert_eq!(
+///     load_config("fox").unwrap_err().to_string(),
+///     "could not load the config (invalid digit found in string)",
+/// );
+/// ```
+///
+/// closures are fine as well, commas in turbofish and all.
+/// ```rust
+/// use std::num::ParseIntError;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// could not load
+///     Load(Box<dyn std::error::Error + Send + Sync>),
+/// }
+///
+/// fn boxed<T: std::error::Error + Send + Sync + 'static, U>(e: T) -> Error {
+///     Error::Load(Box::<T>::new(e))
+/// }
+///
+/// #[foxerror::context(|e: ParseIntError| boxed::<_, ()>(e))]
+/// fn parse(s: &str) -> Result<u8, Error> {
+///     Ok(s.parse::<u8>()?)
+/// }
+///
+/// assert!(matches!(parse("fox"), Err(Error::Load(_))));
+/// ```
+///
+/// a string instead wraps the errors in a `Context` from the
+/// `foxerror-traits` crate with that message, so the function's error type
+/// has to convert from one. the body's errors are boxed, so `?` works on any
+/// of them. `traits_crate = path` after the message points at a re-export of
+/// `foxerror-traits`, like it does on an enum.
+/// ```rust
+/// use foxerror_traits::Context;
+///
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+///     #[err(transparent, from)]
+///     Context(Context),
+/// }
+///
+/// #[foxerror::context("while loading the config")]
+/// fn load_config(port: &str) -> Result<u16, Error> {
+///     let port: u16 = port.parse()?;
+///     if port == 0 {
+///         Err("port 0 is reserved")?;
+///     }
+///     Ok(port)
+/// }
+///
+/// assert_eq!(load_config("8080").unwrap(), 8080);
+/// assert_eq!(
+///     load_config("fox").unwrap_err().to_string(),
+///     "while loading the config: invalid digit found in string",
+/// );
+/// assert_eq!(
+///     load_config("0").unwrap_err().to_string(),
+///     "while loading the config: port 0 is reserved",
+/// );
+/// ```
+#[proc_macro_attribute]
+pub fn context(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    foxerror_core::context(attr.into(), item.into()).into()
+}
+
+/// combine several error types into one enum
+///
+/// each listed type gets a transparent variant named after its path, along
+/// with a `From` implementation so `?` works with any of them. listing the
+/// same type twice is an error.
+///
+/// ```rust
+/// use std::{fmt, io, num};
+///
+/// foxerror::fox_union!(pub enum AppError = io::Error | fmt::Error | num::ParseIntError);
+///
+/// fn parse(s: &str) -> Result<u8, AppError> {
+///     Ok(s.parse()?)
+/// }
+///
+/// let err = parse("fox").unwrap_err();
+/// assert!(matches!(err, AppError::NumParseIntError(_)));
+/// assert_eq!(format!("{err}"), "invalid digit found in string");
+/// let err = AppError::from(io::Error::other("oh no"));
+/// assert!(matches!(err, AppError::IoError(_)));
+/// assert_eq!(format!("{err}"), "oh no");
+/// ```
+///
+/// ```compile_fail
+/// foxerror::fox_union!(enum AppError = std::io::Error | std::io::Error);
+/// ```
+#[proc_macro]
+pub fn fox_union(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    foxerror_core::fox_union(input.into()).into()
+}
+
+/// get the message of a unit variant as a `&'static str`
+///
+/// this is the same string that displaying the variant gives, so it can be
+/// used in constants, tests and documentation. it only works for unit
+/// variants without a template whose enum derives [`FoxError`] with
+/// `#[err(message_consts)]`, which gives the enum a hidden constant for each
+/// of them, and does not know about messages replaced at runtime with
+/// `fmt_if`. enums with type parameters can't have the constants, as
+/// `message!` would have no way of knowing what the parameters are. using it
+/// on any other variant is a compile error saying so.
+///
+/// ```rust
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(message_consts, terminator = ".")]
+/// enum Error {
+///     /// took too long
+///     Timeout,
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// const MSG: &str = foxerror::message!(Error::Timeout);
+/// assert_eq!(MSG, "took too long.");
+/// assert_eq!(MSG, format!("{}", Error::Timeout));
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(message_consts)]
+/// enum Error {
+///     /// bad port
+///     BadPort(u16),
+/// }
+///
+/// const MSG: &str = foxerror::message!(Error::BadPort);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// enum Error {
+///     /// took too long
+///     Timeout,
+/// }
+///
+/// const MSG: &str = foxerror::message!(Error::Timeout);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, foxerror::FoxError)]
+/// #[err(message_consts)]
+/// enum Error<T> {
+///     /// took too long
+///     Timeout,
+///     /// bad value
+///     BadValue(T),
+/// }
+/// ```
+#[proc_macro]
+pub fn message(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    foxerror_core::message(input.into()).into()
 }